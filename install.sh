@@ -78,14 +78,17 @@ case "$SHELL_NAME" in
     zsh)
         CONFIG_FILE="$HOME/.zshrc"
         SOURCE_LINE="source ~/.shorty/aliases"
+        HOOK_LINE='eval "$(shorty used --hook zsh)"'
         ;;
     bash)
         CONFIG_FILE="$HOME/.bashrc"
         SOURCE_LINE="source ~/.shorty/aliases"
+        HOOK_LINE='eval "$(shorty used --hook bash)"'
         ;;
     fish)
         CONFIG_FILE="$HOME/.config/fish/config.fish"
         SOURCE_LINE="test -f ~/.shorty/aliases; and source ~/.shorty/aliases"
+        HOOK_LINE="shorty used --hook fish | source"
         ;;
     *)
         echo "Unsupported shell: $SHELL_NAME. Please manually add 'source ~/.shorty/aliases' to your shell configuration."
@@ -108,6 +111,12 @@ else
     echo "$CONFIG_FILE already contains shorty alias sourcing."
 fi
 
+if ! grep -q "shorty used --hook" "$CONFIG_FILE"; then
+    echo "# Record alias usage for shorty stats" >> "$CONFIG_FILE"
+    echo "$HOOK_LINE" >> "$CONFIG_FILE"
+    echo "Added usage tracking hook to $CONFIG_FILE"
+fi
+
 echo ""
 echo "Installation complete!"
 echo ""
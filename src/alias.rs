@@ -0,0 +1,364 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Alias {
+    pub name: String,
+    pub command: String,
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AliasEntry {
+    pub alias: Alias,
+    pub line_number: usize,
+    pub line_count: usize,
+}
+
+impl Alias {
+    pub fn comment(&self) -> String {
+        let note = self
+            .note
+            .as_ref()
+            .map(|n| format!(" # {n}"))
+            .unwrap_or_default();
+        let tags = if self.tags.is_empty() {
+            String::new()
+        } else {
+            format!(" #tags:{}", self.tags.join(","))
+        };
+        let category = self
+            .category
+            .as_ref()
+            .map(|c| format!(" #category:{c}"))
+            .unwrap_or_default();
+
+        format!("{note}{tags}{category}")
+    }
+}
+
+impl AliasEntry {
+    pub fn to_line(&self) -> String {
+        if self.line_count > 1 {
+            format_function_block(&self.alias.name, &self.alias.command, &self.alias.comment())
+        } else {
+            to_line(&self.alias)
+        }
+    }
+}
+
+pub fn parse_line(line: &str) -> Option<Alias> {
+    let line = line.trim();
+    let rest = line.strip_prefix("alias ")?;
+
+    let eq_pos = rest.find('=')?;
+    let name = rest[..eq_pos].trim().to_string();
+    let rest = rest[eq_pos + 1..].trim();
+
+    let mut command = String::new();
+    let mut remaining = "";
+
+    if rest.starts_with('\'') || rest.starts_with('"') {
+        if let Some((value, after)) = parse_shell_word(rest) {
+            command = value;
+            remaining = after;
+        }
+    } else if let Some(hash_pos) = rest.find('#') {
+        command = rest[..hash_pos].trim().to_string();
+        remaining = &rest[hash_pos..];
+    } else {
+        command = rest.to_string();
+    }
+
+    let (note, tags, category) = parse_comment(remaining);
+
+    Some(Alias {
+        name,
+        command,
+        note,
+        tags,
+        category,
+    })
+}
+
+pub fn to_line(alias: &Alias) -> String {
+    if alias.command.contains('\n') {
+        format_function_block(&alias.name, &alias.command, &alias.comment())
+    } else {
+        format!(
+            "alias {}={}{}",
+            alias.name,
+            shell_quote(&alias.command),
+            alias.comment()
+        )
+    }
+}
+
+pub fn parse_entries(content: &str) -> Vec<AliasEntry> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        if let Some(block) = parse_function_block(&lines[i..]) {
+            let (note, tags, category) = parse_comment(block.comment);
+            entries.push(AliasEntry {
+                alias: Alias {
+                    name: block.name.to_string(),
+                    command: block.body,
+                    note,
+                    tags,
+                    category,
+                },
+                line_number: i + 1,
+                line_count: block.line_count,
+            });
+            i += block.line_count;
+            continue;
+        }
+
+        if let Some(alias) = parse_line(lines[i]) {
+            entries.push(AliasEntry {
+                alias,
+                line_number: i + 1,
+                line_count: 1,
+            });
+        }
+        i += 1;
+    }
+
+    entries
+}
+
+pub fn parse_content(content: &str) -> Vec<Alias> {
+    parse_entries(content)
+        .into_iter()
+        .map(|entry| entry.alias)
+        .collect()
+}
+
+fn parse_comment(text: &str) -> (Option<String>, Vec<String>, Option<String>) {
+    let mut text = text.trim().to_string();
+    let mut note = None;
+    let mut tags = Vec::new();
+    let mut category = None;
+
+    if let Some(category_pos) = text.find("#category:") {
+        let rest = &text[category_pos + 10..];
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        if !rest[..end].is_empty() {
+            category = Some(rest[..end].to_string());
+        }
+        text = format!("{}{}", &text[..category_pos], &rest[end..])
+            .trim()
+            .to_string();
+    }
+    let text = text.as_str();
+
+    let note_part = if let Some(tags_pos) = text.find("#tags:") {
+        tags = text[tags_pos + 6..]
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        text[..tags_pos].trim()
+    } else {
+        text
+    };
+
+    if let Some(stripped) = note_part.strip_prefix('#') {
+        let note_text = stripped.trim();
+        if !note_text.is_empty() {
+            note = Some(note_text.to_string());
+        }
+    }
+
+    // Older versions stored the category as a `category:` tag.
+    if let Some(legacy) = tags.iter().find_map(|tag| tag.strip_prefix("category:")) {
+        category.get_or_insert_with(|| legacy.to_string());
+    }
+    tags.retain(|tag| !tag.starts_with("category:"));
+
+    (note, tags, category)
+}
+
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+pub fn parse_shell_word(input: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(i, ch)) = chars.peek() {
+        match ch {
+            '\'' => {
+                chars.next();
+                loop {
+                    match chars.next()? {
+                        (_, '\'') => break,
+                        (_, c) => value.push(c),
+                    }
+                }
+            }
+            '"' => {
+                chars.next();
+                loop {
+                    match chars.next()? {
+                        (_, '"') => break,
+                        (_, '\\') => match chars.next()? {
+                            (_, c @ ('"' | '\\' | '$' | '`')) => value.push(c),
+                            (_, c) => {
+                                value.push('\\');
+                                value.push(c);
+                            }
+                        },
+                        (_, c) => value.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                chars.next();
+                if let Some((_, c)) = chars.next() {
+                    value.push(c);
+                }
+            }
+            c if c.is_whitespace() => return Some((value, &input[i..])),
+            c => {
+                chars.next();
+                value.push(c);
+            }
+        }
+    }
+
+    Some((value, ""))
+}
+
+const FUNCTION_INDENT: &str = "    ";
+
+pub struct FunctionBlock<'a> {
+    pub name: &'a str,
+    pub comment: &'a str,
+    pub body: String,
+    pub line_count: usize,
+}
+
+pub fn function_block_name(line: &str) -> Option<&str> {
+    let (name, rest) = line.split_once("() {")?;
+    let rest = rest.trim();
+    if name.is_empty()
+        || name.contains(char::is_whitespace)
+        || !(rest.is_empty() || rest.starts_with('#'))
+    {
+        return None;
+    }
+    Some(name)
+}
+
+pub fn parse_function_block<'a>(lines: &[&'a str]) -> Option<FunctionBlock<'a>> {
+    let opening = *lines.first()?;
+    let name = function_block_name(opening)?;
+    let comment = opening.split_once("() {")?.1.trim();
+    let end = lines
+        .iter()
+        .skip(1)
+        .position(|line| line.trim_end() == "}")?
+        + 1;
+
+    let body = &lines[1..end];
+    let indented = body
+        .iter()
+        .all(|line| line.is_empty() || line.starts_with(FUNCTION_INDENT));
+    let body: Vec<&str> = if indented {
+        body.iter()
+            .map(|line| line.strip_prefix(FUNCTION_INDENT).unwrap_or(line))
+            .collect()
+    } else {
+        body.to_vec()
+    };
+
+    Some(FunctionBlock {
+        name,
+        comment,
+        body: body.join("\n"),
+        line_count: end + 1,
+    })
+}
+
+// The body is indented so that a `}` line inside it never closes the block.
+pub fn format_function_block(name: &str, body: &str, comment: &str) -> String {
+    let body: Vec<String> = body
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{FUNCTION_INDENT}{line}")
+            }
+        })
+        .collect();
+    format!("{name}() {{{comment}\n{}\n}}", body.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_fields_round_trip() {
+        let alias = Alias {
+            name: "gs".to_string(),
+            command: "git status".to_string(),
+            note: Some("show status".to_string()),
+            tags: vec!["git".to_string(), "vcs".to_string()],
+            category: Some("git-tools".to_string()),
+        };
+
+        assert_eq!(parse_line(&to_line(&alias)), Some(alias));
+    }
+
+    #[test]
+    fn special_characters_survive_quoting() {
+        for command in [
+            "echo 'single quoted'",
+            "echo \"double quoted\"",
+            "echo $HOME ${PATH} $(date)",
+            "printf '%s\\n' \"it's $USER\"",
+        ] {
+            let alias = Alias {
+                name: "t".to_string(),
+                command: command.to_string(),
+                ..Alias::default()
+            };
+            let line = to_line(&alias);
+            assert!(line.starts_with("alias t='"), "{line}");
+            assert_eq!(parse_line(&line), Some(alias));
+        }
+    }
+
+    #[test]
+    fn function_block_with_closing_brace_in_body_round_trips() {
+        let alias = Alias {
+            name: "mg".to_string(),
+            command: "if true; then\n{ echo x;\n}\nfi".to_string(),
+            ..Alias::default()
+        };
+        let content = format!("{}\nalias ll='ls -l'\n", to_line(&alias));
+
+        let entries = parse_entries(&content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].alias, alias);
+        assert_eq!(entries[0].line_count, 6);
+        assert_eq!(entries[1].alias.name, "ll");
+    }
+
+    #[test]
+    fn unindented_function_block_still_parses() {
+        let lines = ["mf() { # note", "echo a", "echo b", "}"];
+        let block = parse_function_block(&lines).unwrap();
+        assert_eq!(block.name, "mf");
+        assert_eq!(block.body, "echo a\necho b");
+        assert_eq!(block.line_count, 4);
+    }
+}
@@ -1,52 +1,150 @@
-use crate::commands::remove::remove_alias;
-use crate::utils::get_aliases_path;
-use std::fs;
-use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use crate::alias::{self, parse_content, parse_entries, Alias};
+use crate::commands::backup::backup_before_edit;
+use crate::commands::categories::command_pattern;
+use crate::commands::history::record_operation;
+use crate::commands::plugins::run_alias_hooks;
+use crate::utils::{acquire_lock, get_aliases_path, read_aliases_file, write_aliases_atomic};
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Debug, Default)]
+pub enum Placement {
+    #[default]
+    End,
+    Before(String),
+    After(String),
+}
+
+impl Placement {
+    fn target(&self) -> Option<&str> {
+        match self {
+            Placement::End => None,
+            Placement::Before(target) | Placement::After(target) => Some(target),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AddOptions {
+    pub auto_tag: bool,
+    pub multiline: bool,
+    pub overwrite_if_different: bool,
+    pub placement: Placement,
+}
 
 pub fn add_alias(
     alias: &str,
     command: &str,
     note: &Option<String>,
     tags: &[String],
+    options: &AddOptions,
 ) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
-
-    if alias_exists(&aliases_path, alias)? {
-        print!("Warning: Alias '{alias}' already exists. Do you want to overwrite it? (y/n): ");
-        io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        if input.trim().eq_ignore_ascii_case("y") {
-            remove_alias(alias)?;
-        } else {
-            println!("Operation aborted.");
-            return Ok(());
+    let aliases_path = get_aliases_path()?;
+    let command = if options.multiline {
+        command.replace("\\n", "\n")
+    } else {
+        command.to_string()
+    };
+    let command = command.as_str();
+
+    let mut tags = tags.to_vec();
+    if options.auto_tag {
+        let pattern = command_pattern(command);
+        if pattern != "general" && !tags.iter().any(|t| t == pattern) {
+            tags.push(pattern.to_string());
+        }
+    }
+
+    let existing = existing_alias(&aliases_path, alias)?;
+    let entry = Alias {
+        name: alias.to_string(),
+        command: command.to_string(),
+        note: note.clone(),
+        tags,
+        category: existing.as_ref().and_then(|e| e.category.clone()),
+    };
+
+    if let Some(target) = options.placement.target() {
+        if target == alias {
+            anyhow::bail!("Cannot place alias '{}' relative to itself", alias);
+        }
+        if existing_alias(&aliases_path, target)?.is_none() {
+            anyhow::bail!("Alias '{}' not found", target);
         }
     }
 
-    if aliases_path.exists() {
-        let content = fs::read(&aliases_path)?;
-        if !content.is_empty() && !content.ends_with(&[b'\n']) {
-            let mut file = fs::OpenOptions::new().append(true).open(&aliases_path)?;
-            writeln!(file)?;
+    let mut overwrite = false;
+    if let Some(existing) = existing {
+        if existing == entry {
+            println!("Alias '{alias}' is unchanged.");
+            return Ok(());
+        }
+
+        if !options.overwrite_if_different {
+            print!("Warning: Alias '{alias}' already exists. Do you want to overwrite it? (y/n): ");
+            io::stdout().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            if !input.trim().eq_ignore_ascii_case("y") {
+                println!("Operation aborted.");
+                return Ok(());
+            }
         }
+        overwrite = true;
     }
 
-    let mut file = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&aliases_path)?;
+    run_alias_hooks("pre_add", &entry)?;
 
-    let tags_str = if tags.is_empty() {
+    let lock = acquire_lock()?;
+
+    let mut content = if aliases_path.exists() {
+        read_aliases_file(&aliases_path)?
+    } else {
         String::new()
+    };
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+
+    let replaced = if overwrite {
+        parse_entries(&content)
+            .into_iter()
+            .find(|existing| existing.alias.name == alias)
     } else {
-        format!(" #tags:{}", tags.join(","))
+        None
     };
 
-    let note_comment = note.as_ref().map(|n| format!(" # {n}")).unwrap_or_default();
+    match (replaced, options.placement.target()) {
+        (Some(replaced), None) => {
+            let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+            let start = replaced.line_number - 1;
+            lines.splice(start..start + replaced.line_count, [alias::to_line(&entry)]);
+            content = lines.join("\n");
+            content.push('\n');
+        }
+        (replaced, Some(target)) => {
+            if let Some(replaced) = replaced {
+                let mut lines: Vec<&str> = content.lines().collect();
+                let start = replaced.line_number - 1;
+                lines.drain(start..start + replaced.line_count);
+                content = lines.join("\n");
+                content.push('\n');
+            }
+            content = insert_near(&content, &entry, target, &options.placement)?;
+        }
+        (None, None) => {
+            content.push_str(&alias::to_line(&entry));
+            content.push('\n');
+        }
+    }
+    if overwrite {
+        backup_before_edit()?;
+    }
+    write_aliases_atomic(&content)?;
+    drop(lock);
 
-    writeln!(file, "alias {alias}='{command}'{note_comment}{tags_str}")?;
+    record_operation("add", &format!("{alias} -> {command}"));
+    run_alias_hooks("post_add", &entry)?;
 
     println!("Added alias: {alias} -> {command}");
     println!("To apply the changes, please restart your terminal!");
@@ -54,13 +152,134 @@ pub fn add_alias(
     Ok(())
 }
 
-fn alias_exists(aliases_path: &PathBuf, alias: &str) -> io::Result<bool> {
-    if let Ok(file) = fs::File::open(aliases_path) {
-        for line in io::BufReader::new(file).lines().map_while(Result::ok) {
-            if line.starts_with(&format!("alias {alias}=")) {
-                return Ok(true);
-            }
+fn insert_near(
+    content: &str,
+    entry: &Alias,
+    target: &str,
+    placement: &Placement,
+) -> anyhow::Result<String> {
+    let target_entry = parse_entries(content)
+        .into_iter()
+        .find(|existing| existing.alias.name == target)
+        .ok_or_else(|| anyhow::anyhow!("Alias '{}' not found", target))?;
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let index = match placement {
+        Placement::After(_) => target_entry.line_number - 1 + target_entry.line_count,
+        _ => target_entry.line_number - 1,
+    };
+    lines.insert(index, alias::to_line(entry));
+
+    let mut new_content = lines.join("\n");
+    new_content.push('\n');
+    Ok(new_content)
+}
+
+fn existing_alias(aliases_path: &Path, alias: &str) -> anyhow::Result<Option<Alias>> {
+    if !aliases_path.exists() {
+        return Ok(None);
+    }
+
+    let content = read_aliases_file(aliases_path)?;
+    Ok(parse_content(&content)
+        .into_iter()
+        .find(|existing| existing.name == alias))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+    use std::fs;
+
+    const CONTENT: &str = "alias gs='git status'\nalias ll='ls -la'\n";
+
+    fn overwrite_if_different() -> AddOptions {
+        AddOptions {
+            overwrite_if_different: true,
+            ..AddOptions::default()
         }
     }
-    Ok(false)
+
+    #[test]
+    fn identical_alias_leaves_the_file_untouched() {
+        let home = FakeHome::new("add-identical");
+        let path = home.write("aliases", CONTENT);
+
+        add_alias("gs", "git status", &None, &[], &overwrite_if_different()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), CONTENT);
+        assert!(!home.path().join(".shorty").join("backups").exists());
+    }
+
+    #[test]
+    fn differing_alias_is_replaced_in_place() {
+        let home = FakeHome::new("add-different");
+        let path = home.write("aliases", CONTENT);
+
+        add_alias("gs", "git status -s", &None, &[], &overwrite_if_different()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "alias gs='git status -s'\nalias ll='ls -la'\n"
+        );
+    }
+
+    #[test]
+    fn new_alias_is_inserted_before_or_after_its_target() {
+        let entry = Alias {
+            name: "gp".to_string(),
+            command: "git push".to_string(),
+            ..Alias::default()
+        };
+
+        let before = insert_near(CONTENT, &entry, "ll", &Placement::Before("ll".into())).unwrap();
+        assert_eq!(
+            before,
+            "alias gs='git status'\nalias gp='git push'\nalias ll='ls -la'\n"
+        );
+
+        let after = insert_near(CONTENT, &entry, "gs", &Placement::After("gs".into())).unwrap();
+        assert_eq!(after, before);
+
+        let after_last =
+            insert_near(CONTENT, &entry, "ll", &Placement::After("ll".into())).unwrap();
+        assert_eq!(
+            after_last,
+            "alias gs='git status'\nalias ll='ls -la'\nalias gp='git push'\n"
+        );
+    }
+
+    #[test]
+    fn insertion_after_a_function_skips_its_whole_block() {
+        let function = alias::to_line(&Alias {
+            name: "mk".to_string(),
+            command: "mkdir -p \"$1\"\ncd \"$1\"".to_string(),
+            ..Alias::default()
+        });
+        let content = format!("{function}\nalias ll='ls -la'\n");
+        let entry = Alias {
+            name: "gs".to_string(),
+            command: "git status".to_string(),
+            ..Alias::default()
+        };
+
+        let updated = insert_near(&content, &entry, "mk", &Placement::After("mk".into())).unwrap();
+        assert_eq!(
+            updated,
+            format!("{function}\nalias gs='git status'\nalias ll='ls -la'\n")
+        );
+    }
+
+    #[test]
+    fn tag_from_command_tags_git_commands_with_git() {
+        let home = FakeHome::new("add-auto-tag");
+        let options = AddOptions {
+            auto_tag: true,
+            ..AddOptions::default()
+        };
+
+        add_alias("gco", "git checkout", &None, &[], &options).unwrap();
+
+        let content = fs::read_to_string(home.path().join(".shorty").join("aliases")).unwrap();
+        assert_eq!(parse_content(&content)[0].tags, ["git"]);
+    }
 }
@@ -1,10 +1,16 @@
-use crate::utils::get_aliases_path;
+use crate::alias::parse_content;
+use crate::commands::config::load_config;
+use crate::commands::history::record_operation;
+use crate::utils::{
+    expand_home, get_aliases_path, read_aliases_file, write_aliases_atomic, OutputFormat,
+};
 use chrono::{DateTime, Local, Utc};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn create_backup(custom_name: Option<&str>) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
+    let aliases_path = get_aliases_path()?;
 
     if !aliases_path.exists() {
         anyhow::bail!("Aliases file not found. Nothing to backup.");
@@ -26,10 +32,16 @@ pub fn create_backup(custom_name: Option<&str>) -> anyhow::Result<()> {
     println!("Backup created: {}", backup_path.display());
     println!("Aliases backed up successfully!");
 
+    let max_backups = load_config().backup.max_backups as usize;
+    let pruned = prune_backups(&backup_dir, max_backups)?;
+    if pruned > 0 {
+        println!("Removed {pruned} old backup(s) (backup.max_backups = {max_backups})");
+    }
+
     Ok(())
 }
 
-pub fn restore_backup(backup_file: &str) -> anyhow::Result<()> {
+fn resolve_backup_path(backup_file: &str) -> anyhow::Result<PathBuf> {
     let backup_path = if backup_file.starts_with('/') {
         PathBuf::from(backup_file)
     } else {
@@ -40,10 +52,32 @@ pub fn restore_backup(backup_file: &str) -> anyhow::Result<()> {
         anyhow::bail!("Backup file not found: {}", backup_path.display());
     }
 
+    Ok(backup_path)
+}
+
+pub fn restore_backup(backup_file: &str, into: Option<&str>) -> anyhow::Result<()> {
+    let backup_path = resolve_backup_path(backup_file)?;
+
+    if let Some(target) = into {
+        let target_path = expand_home(target);
+        if let Some(parent) = target_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&backup_path, &target_path)?;
+
+        println!(
+            "Restored {} into {}",
+            backup_path.display(),
+            target_path.display()
+        );
+        println!("The active aliases file was not modified.");
+        return Ok(());
+    }
+
     create_backup(Some("pre_restore"))?;
 
-    let aliases_path = get_aliases_path();
-    fs::copy(&backup_path, &aliases_path)?;
+    write_aliases_atomic(&read_aliases_file(&backup_path)?)?;
+    record_operation("restore", &backup_path.display().to_string());
 
     println!("Restored from backup: {}", backup_path.display());
     println!("To apply the changes, please restart your terminal!");
@@ -51,9 +85,98 @@ pub fn restore_backup(backup_file: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn list_backups() -> anyhow::Result<()> {
+fn alias_commands(path: &Path) -> anyhow::Result<BTreeMap<String, String>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    Ok(command_map(&read_aliases_file(path)?))
+}
+
+pub(crate) fn command_map(content: &str) -> BTreeMap<String, String> {
+    parse_content(content)
+        .into_iter()
+        .map(|alias| (alias.name, alias.command))
+        .collect()
+}
+
+pub fn compare_backups(first: &str, second: Option<&str>) -> anyhow::Result<()> {
+    let first_path = resolve_backup_path(first)?;
+    let second_path = match second {
+        Some(second) => resolve_backup_path(second)?,
+        None => get_aliases_path()?,
+    };
+
+    let old = alias_commands(&first_path)?;
+    let new = alias_commands(&second_path)?;
+
+    println!(
+        "Comparing {} -> {}\n",
+        first_path.display(),
+        second_path.display()
+    );
+    print_alias_diff(&old, &new);
+
+    Ok(())
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct AliasDiff<'a> {
+    added: Vec<(&'a str, &'a str)>,
+    removed: Vec<(&'a str, &'a str)>,
+    changed: Vec<(&'a str, &'a str, &'a str)>,
+}
+
+fn alias_diff<'a>(
+    old: &'a BTreeMap<String, String>,
+    new: &'a BTreeMap<String, String>,
+) -> AliasDiff<'a> {
+    let mut diff = AliasDiff::default();
+    for (name, command) in old {
+        match new.get(name) {
+            None => diff.removed.push((name, command)),
+            Some(new_command) if new_command != command => {
+                diff.changed.push((name, command, new_command))
+            }
+            Some(_) => {}
+        }
+    }
+    for (name, command) in new {
+        if !old.contains_key(name) {
+            diff.added.push((name, command));
+        }
+    }
+    diff
+}
+
+pub(crate) fn print_alias_diff(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) {
+    let diff = alias_diff(old, new);
+    for (name, command) in &diff.removed {
+        println!("- {name} = {command}");
+    }
+    for (name, command, new_command) in &diff.changed {
+        println!("~ {name}: {command} -> {new_command}");
+    }
+    for (name, command) in &diff.added {
+        println!("+ {name} = {command}");
+    }
+
+    let (added, removed, changed) = (diff.added.len(), diff.removed.len(), diff.changed.len());
+    if added + removed + changed == 0 {
+        println!("No differences.");
+    } else {
+        println!("\n{added} added, {removed} removed, {changed} changed");
+    }
+}
+
+pub fn list_backups(output: OutputFormat) -> anyhow::Result<()> {
     let backup_dir = get_backup_dir()?;
 
+    if !backup_dir.exists() && output == OutputFormat::Json {
+        println!("[]");
+        return Ok(());
+    }
+
     if !backup_dir.exists() {
         println!("No backups found. Backup directory doesn't exist.");
         return Ok(());
@@ -75,13 +198,29 @@ pub fn list_backups() -> anyhow::Result<()> {
         }
     }
 
+    backups.sort_by_key(|b| std::cmp::Reverse(b.1));
+
+    if output == OutputFormat::Json {
+        let value: Vec<serde_json::Value> = backups
+            .iter()
+            .map(|(path, datetime, size)| {
+                serde_json::json!({
+                    "name": path.file_name().unwrap().to_string_lossy(),
+                    "path": path.display().to_string(),
+                    "created": datetime.to_rfc3339(),
+                    "size_bytes": size,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
     if backups.is_empty() {
         println!("No backup files found.");
         return Ok(());
     }
 
-    backups.sort_by(|a, b| b.1.cmp(&a.1));
-
     println!("Available backups:");
     println!("{:<30} {:<20} {:<10}", "Name", "Created", "Size");
     println!("{}", "-".repeat(60));
@@ -149,13 +288,47 @@ fn get_backup_dir() -> anyhow::Result<PathBuf> {
     Ok(home_dir.join(".shorty").join("backups"))
 }
 
+fn prune_backups(backup_dir: &Path, max_backups: usize) -> anyhow::Result<usize> {
+    if max_backups == 0 {
+        return Ok(0);
+    }
+
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("aliases_backup_") && name.ends_with(".txt"))
+        })
+        .collect();
+
+    if backups.len() <= max_backups {
+        return Ok(0);
+    }
+
+    backups.sort();
+    let to_remove = backups.len() - max_backups;
+    for path in &backups[..to_remove] {
+        fs::remove_file(path)?;
+    }
+
+    Ok(to_remove)
+}
+
+pub fn backup_before_edit() -> anyhow::Result<()> {
+    if load_config().backup.backup_before_edit {
+        auto_backup()?;
+    }
+    Ok(())
+}
+
 pub fn auto_backup() -> anyhow::Result<()> {
     let backup_dir = get_backup_dir()?;
     if !backup_dir.exists() {
         fs::create_dir_all(&backup_dir)?;
     }
 
-    let aliases_path = get_aliases_path();
+    let aliases_path = get_aliases_path()?;
     if !aliases_path.exists() {
         return Ok(());
     }
@@ -176,3 +349,50 @@ pub fn auto_backup() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+
+    #[test]
+    fn comparing_backups_reports_added_removed_and_changed() {
+        let home = FakeHome::new("backup-compare");
+        home.write(
+            "backups/first.bak",
+            "alias gs='git status'\nalias ll='ls -la'\nalias gp='git push'\n",
+        );
+        home.write(
+            "backups/second.bak",
+            "alias gs='git status -s'\nalias gp='git push'\nalias gd='git diff'\n",
+        );
+
+        let old = alias_commands(&resolve_backup_path("first.bak").unwrap()).unwrap();
+        let new = alias_commands(&resolve_backup_path("second.bak").unwrap()).unwrap();
+
+        assert_eq!(
+            alias_diff(&old, &new),
+            AliasDiff {
+                added: vec![("gd", "git diff")],
+                removed: vec![("ll", "ls -la")],
+                changed: vec![("gs", "git status", "git status -s")],
+            }
+        );
+    }
+
+    #[test]
+    fn restore_into_leaves_the_live_file_untouched() {
+        let home = FakeHome::new("backup-restore-into");
+        let live = "alias gs='git status'\n";
+        let aliases = home.write("aliases", live);
+        let backup = "alias old='echo old'\n";
+        home.write("backups/old.bak", backup);
+        let target = home.path().join("scratch").join("restored");
+
+        restore_backup("old.bak", target.to_str()).unwrap();
+
+        assert_eq!(fs::read(&aliases).unwrap(), live.as_bytes());
+        assert_eq!(fs::read_to_string(&target).unwrap(), backup);
+        assert!(!home.path().join(".shorty/backups/pre_restore.txt").exists());
+    }
+}
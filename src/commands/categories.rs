@@ -1,6 +1,16 @@
-use crate::utils::get_aliases_path;
+use crate::alias::{parse_content, parse_entries, Alias};
+use crate::commands::config::load_config;
+use crate::utils::{
+    acquire_lock, get_aliases_path, read_aliases_file, write_aliases_atomic, OutputFormat,
+};
+use crossterm::style::{Color, Stylize};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    io::{self, IsTerminal, Write},
+    path::PathBuf,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Category {
@@ -13,6 +23,54 @@ pub struct Category {
     pub alias_count: usize,
 }
 
+#[derive(Debug)]
+pub enum CategorySort {
+    Name,
+    Count,
+    Created,
+}
+
+impl std::str::FromStr for CategorySort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(CategorySort::Name),
+            "count" => Ok(CategorySort::Count),
+            "created" => Ok(CategorySort::Created),
+            _ => anyhow::bail!(
+                "Unsupported sort order: {}. Supported: name, count, created",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CategoryNode {
+    name: String,
+    alias_count: usize,
+    icon: Option<String>,
+    color: Option<String>,
+    children: Vec<CategoryNode>,
+}
+
+#[derive(Debug, Serialize)]
+struct CategoryDetails<'a> {
+    #[serde(flatten)]
+    category: &'a Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_alias_count: Option<usize>,
+    children: Vec<&'a str>,
+    aliases: Vec<CategoryAlias>,
+}
+
+#[derive(Debug, Serialize)]
+struct CategoryAlias {
+    name: String,
+    command: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct CategoriesData {
     version: String,
@@ -26,6 +84,7 @@ pub fn add_category(
     color: Option<&str>,
     icon: Option<&str>,
 ) -> anyhow::Result<()> {
+    validate_category_name(name)?;
     let mut categories = load_categories()?;
 
     if categories.iter().any(|c| c.name == name) {
@@ -38,6 +97,10 @@ pub fn add_category(
         }
     }
 
+    if let Some(color) = color {
+        parse_color(color)?;
+    }
+
     let category = Category {
         name: name.to_string(),
         description: description.unwrap_or("No description").to_string(),
@@ -62,9 +125,24 @@ pub fn add_category(
     Ok(())
 }
 
-pub fn list_categories(show_tree: bool, show_counts: bool) -> anyhow::Result<()> {
+pub fn list_categories(
+    show_tree: bool,
+    show_counts: bool,
+    sort: Option<CategorySort>,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
     let mut categories = load_categories()?;
 
+    if output == OutputFormat::Json {
+        update_alias_counts(&mut categories)?;
+        if let Some(sort) = &sort {
+            sort_categories(&mut categories, sort);
+        }
+        let tree = build_category_tree(&categories, None);
+        println!("{}", serde_json::to_string_pretty(&tree)?);
+        return Ok(());
+    }
+
     if categories.is_empty() {
         println!("No categories found. Create your first category with 'shorty category add'");
         return Ok(());
@@ -75,6 +153,13 @@ pub fn list_categories(show_tree: bool, show_counts: bool) -> anyhow::Result<()>
         save_categories(&categories)?;
     }
 
+    if let Some(sort) = &sort {
+        if matches!(sort, CategorySort::Count) && !show_counts {
+            update_alias_counts(&mut categories)?;
+        }
+        sort_categories(&mut categories, sort);
+    }
+
     if show_tree {
         display_category_tree(&categories)?;
     } else {
@@ -114,69 +199,108 @@ pub fn remove_category(name: &str, force: bool) -> anyhow::Result<()> {
         }
     }
 
+    let uncategorized = if alias_count > 0 {
+        clear_alias_category(name)?
+    } else {
+        0
+    };
+
     categories.remove(category_index);
     save_categories(&categories)?;
 
     println!("Category '{name}' removed successfully");
-    if alias_count > 0 {
-        println!("{alias_count} aliases are now uncategorized");
+    if uncategorized > 0 {
+        println!("{uncategorized} aliases are now uncategorized");
     }
 
     Ok(())
 }
 
+fn clear_alias_category(category_name: &str) -> anyhow::Result<usize> {
+    let _lock = acquire_lock()?;
+    let aliases_path = get_aliases_path()?;
+    if !aliases_path.exists() {
+        return Ok(0);
+    }
+
+    let content = read_aliases_file(&aliases_path)?;
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut cleared = 0;
+
+    for mut entry in parse_entries(&content).into_iter().rev() {
+        if entry.alias.category.as_deref() != Some(category_name) {
+            continue;
+        }
+        entry.alias.category = None;
+        let start = entry.line_number - 1;
+        lines.splice(start..start + entry.line_count, [entry.to_line()]);
+        cleared += 1;
+    }
+
+    if cleared > 0 {
+        let mut new_content = lines.join("\n");
+        new_content.push('\n');
+        write_aliases_atomic(&new_content)?;
+    }
+
+    Ok(cleared)
+}
+
 pub fn move_alias_to_category(alias_name: &str, category_name: &str) -> anyhow::Result<()> {
+    validate_category_name(category_name)?;
     let categories = load_categories()?;
 
     if !categories.iter().any(|c| c.name == category_name) {
         anyhow::bail!("Category '{}' does not exist", category_name);
     }
 
-    let aliases_path = get_aliases_path();
+    let _lock = acquire_lock()?;
+    let aliases_path = get_aliases_path()?;
     if !aliases_path.exists() {
         anyhow::bail!("No aliases file found");
     }
 
-    let content = fs::read_to_string(&aliases_path)?;
-    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-    let mut found = false;
-
-    for line in &mut lines {
-        if let Some((name, command, note, mut tags)) = parse_alias_line(line) {
-            if name == alias_name {
-                found = true;
-
-                tags.retain(|tag| !tag.starts_with("category:"));
-
-                tags.push(format!("category:{category_name}"));
-
-                *line = build_alias_line(&name, &command, note.as_deref(), &tags);
-                break;
-            }
-        }
-    }
+    let content = read_aliases_file(&aliases_path)?;
+    let mut entry = parse_entries(&content)
+        .into_iter()
+        .find(|entry| entry.alias.name == alias_name)
+        .ok_or_else(|| anyhow::anyhow!("Alias '{}' not found", alias_name))?;
+    entry.alias.category = Some(category_name.to_string());
 
-    if !found {
-        anyhow::bail!("Alias '{}' not found", alias_name);
-    }
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let start = entry.line_number - 1;
+    lines.splice(start..start + entry.line_count, [entry.to_line()]);
 
-    let new_content = lines.join("\n");
-    fs::write(&aliases_path, new_content)?;
+    let mut new_content = lines.join("\n");
+    new_content.push('\n');
+    write_aliases_atomic(&new_content)?;
 
     println!("Moved alias '{alias_name}' to category '{category_name}'");
 
     Ok(())
 }
 
-pub fn show_category(name: &str) -> anyhow::Result<()> {
+pub fn show_category(name: &str, recursive: bool, output: OutputFormat) -> anyhow::Result<()> {
     let mut categories = load_categories()?;
 
-    let category = categories
-        .iter_mut()
-        .find(|c| c.name == name)
+    let index = categories
+        .iter()
+        .position(|c| c.name == name)
         .ok_or_else(|| anyhow::anyhow!("Category '{}' not found", name))?;
 
-    category.alias_count = count_aliases_in_category(name)?;
+    categories[index].alias_count = count_aliases_in_category(name)?;
+    let total_alias_count = if recursive {
+        Some(recursive_alias_count(&categories, name)?)
+    } else {
+        None
+    };
+    let category = &categories[index];
+
+    if output == OutputFormat::Json {
+        let details = category_details(&categories, category, total_alias_count)?;
+        println!("{}", serde_json::to_string_pretty(&details)?);
+        return Ok(());
+    }
 
     println!("Category: {}", category.name);
     println!("Description: {}", category.description);
@@ -194,6 +318,9 @@ pub fn show_category(name: &str) -> anyhow::Result<()> {
     }
 
     println!("Aliases: {}", category.alias_count);
+    if let Some(total) = total_alias_count {
+        println!("Aliases (including subcategories): {total}");
+    }
     println!("Created: {}", category.created_at);
 
     let children: Vec<_> = categories
@@ -224,45 +351,53 @@ pub fn show_category(name: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn category_details<'a>(
+    categories: &'a [Category],
+    category: &'a Category,
+    total_alias_count: Option<usize>,
+) -> anyhow::Result<CategoryDetails<'a>> {
+    Ok(CategoryDetails {
+        category,
+        total_alias_count,
+        children: categories
+            .iter()
+            .filter(|c| c.parent.as_deref() == Some(category.name.as_str()))
+            .map(|c| c.name.as_str())
+            .collect(),
+        aliases: get_aliases_in_category(&category.name)?
+            .into_iter()
+            .map(|(name, command)| CategoryAlias { name, command })
+            .collect(),
+    })
+}
+
 pub fn group_aliases_by_category() -> anyhow::Result<()> {
     let categories = load_categories()?;
-    let aliases_path = get_aliases_path();
+    let aliases_path = get_aliases_path()?;
 
     if !aliases_path.exists() {
         println!("No aliases file found");
         return Ok(());
     }
 
-    let content = fs::read_to_string(&aliases_path)?;
-    let mut categorized_aliases: HashMap<String, Vec<(String, String, Option<String>)>> =
-        HashMap::new();
-    let mut uncategorized_aliases = Vec::new();
-
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-
-        if let Some((name, command, note, tags)) = parse_alias_line(line) {
-            let category = tags
-                .iter()
-                .find(|tag| tag.starts_with("category:"))
-                .map(|tag| tag[9..].to_string())
-                .unwrap_or_else(|| "uncategorized".to_string());
+    write_grouped_aliases(&categories, &mut io::stdout().lock())
+}
 
-            if category == "uncategorized" {
-                uncategorized_aliases.push((name, command, note));
-            } else {
-                categorized_aliases
-                    .entry(category)
-                    .or_default()
-                    .push((name, command, note));
-            }
-        }
-    }
+fn write_grouped_aliases(categories: &[Category], out: &mut impl Write) -> anyhow::Result<()> {
+    let mut grouped = aliases_by_category()?;
+    let entries = |aliases: Vec<Alias>| -> Vec<(String, String, Option<String>)> {
+        aliases
+            .into_iter()
+            .map(|alias| (alias.name, alias.command, alias.note))
+            .collect()
+    };
+    let uncategorized_aliases = entries(grouped.remove(&None).unwrap_or_default());
+    let categorized_aliases: BTreeMap<String, Vec<(String, String, Option<String>)>> = grouped
+        .into_iter()
+        .filter_map(|(category, aliases)| Some((category?, entries(aliases))))
+        .collect();
 
-    println!("Aliases grouped by category:\n");
+    writeln!(out, "Aliases grouped by category:\n")?;
 
     for (category_name, aliases) in &categorized_aliases {
         let category_info = categories.iter().find(|c| c.name == *category_name);
@@ -277,7 +412,7 @@ pub fn group_aliases_by_category() -> anyhow::Result<()> {
             format!("{category_name} (category not found)")
         };
 
-        println!("{} ({} aliases):", display_name, aliases.len());
+        writeln!(out, "{} ({} aliases):", display_name, aliases.len())?;
 
         for (alias_name, command, note) in aliases {
             let display_command = if command.len() > 40 {
@@ -287,16 +422,20 @@ pub fn group_aliases_by_category() -> anyhow::Result<()> {
             };
 
             if let Some(note_text) = note {
-                println!("  • {alias_name} → {display_command} # {note_text}");
+                writeln!(out, "  • {alias_name} → {display_command} # {note_text}")?;
             } else {
-                println!("  • {alias_name} → {display_command}");
+                writeln!(out, "  • {alias_name} → {display_command}")?;
             }
         }
-        println!();
+        writeln!(out)?;
     }
 
     if !uncategorized_aliases.is_empty() {
-        println!("Uncategorized ({} aliases):", uncategorized_aliases.len());
+        writeln!(
+            out,
+            "Uncategorized ({} aliases):",
+            uncategorized_aliases.len()
+        )?;
         for (alias_name, command, note) in &uncategorized_aliases {
             let display_command = if command.len() > 40 {
                 format!("{}...", &command[..37])
@@ -305,20 +444,21 @@ pub fn group_aliases_by_category() -> anyhow::Result<()> {
             };
 
             if let Some(note_text) = note {
-                println!("  • {alias_name} → {display_command} # {note_text}");
+                writeln!(out, "  • {alias_name} → {display_command} # {note_text}")?;
             } else {
-                println!("  • {alias_name} → {display_command}");
+                writeln!(out, "  • {alias_name} → {display_command}")?;
             }
         }
-        println!();
+        writeln!(out)?;
     }
 
     let total_categorized: usize = categorized_aliases.values().map(|v| v.len()).sum();
     let total_aliases = total_categorized + uncategorized_aliases.len();
 
-    println!("Summary:");
-    println!("  Total aliases: {total_aliases}");
-    println!(
+    writeln!(out, "Summary:")?;
+    writeln!(out, "  Total aliases: {total_aliases}")?;
+    writeln!(
+        out,
         "  Categorized: {} ({:.1}%)",
         total_categorized,
         if total_aliases > 0 {
@@ -326,8 +466,9 @@ pub fn group_aliases_by_category() -> anyhow::Result<()> {
         } else {
             0.0
         }
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "  Uncategorized: {} ({:.1}%)",
         uncategorized_aliases.len(),
         if total_aliases > 0 {
@@ -335,14 +476,17 @@ pub fn group_aliases_by_category() -> anyhow::Result<()> {
         } else {
             0.0
         }
-    );
+    )?;
 
     if !uncategorized_aliases.is_empty() {
-        println!("\nSuggestions:");
+        writeln!(out, "\nSuggestions:")?;
         let command_patterns = analyze_command_patterns(&uncategorized_aliases);
         for (pattern, count) in command_patterns {
             if count > 1 {
-                println!("  • Create '{pattern}' category for {count} similar commands");
+                writeln!(
+                    out,
+                    "  • Create '{pattern}' category for {count} similar commands"
+                )?;
             }
         }
     }
@@ -350,6 +494,17 @@ pub fn group_aliases_by_category() -> anyhow::Result<()> {
     Ok(())
 }
 
+// The category is stored as a single `#category:<name>` word in the alias comment.
+fn validate_category_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '#') {
+        anyhow::bail!(
+            "Invalid category name: '{}'. Category names cannot contain spaces or '#'",
+            name
+        );
+    }
+    Ok(())
+}
+
 fn load_categories() -> anyhow::Result<Vec<Category>> {
     let categories_path = get_categories_path()?;
 
@@ -390,44 +545,145 @@ fn get_categories_path() -> anyhow::Result<PathBuf> {
     Ok(home_dir.join(".shorty").join("categories.toml"))
 }
 
+fn sort_categories(categories: &mut [Category], sort: &CategorySort) {
+    match sort {
+        CategorySort::Name => categories.sort_by(|a, b| a.name.cmp(&b.name)),
+        CategorySort::Count => categories.sort_by(|a, b| {
+            b.alias_count
+                .cmp(&a.alias_count)
+                .then_with(|| a.name.cmp(&b.name))
+        }),
+        CategorySort::Created => categories.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.name.cmp(&b.name))
+        }),
+    }
+}
+
+fn aliases_by_category() -> anyhow::Result<BTreeMap<Option<String>, Vec<Alias>>> {
+    let aliases_path = get_aliases_path()?;
+    let mut grouped: BTreeMap<Option<String>, Vec<Alias>> = BTreeMap::new();
+    if !aliases_path.exists() {
+        return Ok(grouped);
+    }
+
+    for alias in parse_content(&read_aliases_file(&aliases_path)?) {
+        grouped
+            .entry(alias.category.clone())
+            .or_default()
+            .push(alias);
+    }
+    for aliases in grouped.values_mut() {
+        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    Ok(grouped)
+}
+
 fn update_alias_counts(categories: &mut [Category]) -> anyhow::Result<()> {
+    let grouped = aliases_by_category()?;
     for category in categories {
-        category.alias_count = count_aliases_in_category(&category.name)?;
+        category.alias_count = grouped
+            .get(&Some(category.name.clone()))
+            .map_or(0, Vec::len);
     }
     Ok(())
 }
 
+fn recursive_alias_count(categories: &[Category], name: &str) -> anyhow::Result<usize> {
+    let grouped = aliases_by_category()?;
+    Ok(descendant_names(categories, name)
+        .iter()
+        .chain([&name.to_string()])
+        .map(|category| grouped.get(&Some(category.clone())).map_or(0, Vec::len))
+        .sum())
+}
+
+fn descendant_names(categories: &[Category], name: &str) -> Vec<String> {
+    let mut seen = HashSet::from([name.to_string()]);
+    let mut pending = vec![name.to_string()];
+    let mut descendants = Vec::new();
+
+    while let Some(parent) = pending.pop() {
+        for child in categories
+            .iter()
+            .filter(|c| c.parent.as_deref() == Some(parent.as_str()))
+        {
+            if seen.insert(child.name.clone()) {
+                pending.push(child.name.clone());
+                descendants.push(child.name.clone());
+            }
+        }
+    }
+
+    descendants
+}
+
 fn count_aliases_in_category(category_name: &str) -> anyhow::Result<usize> {
     let aliases = get_aliases_in_category(category_name)?;
     Ok(aliases.len())
 }
 
 fn get_aliases_in_category(category_name: &str) -> anyhow::Result<Vec<(String, String)>> {
-    let aliases_path = get_aliases_path();
-    if !aliases_path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let content = fs::read_to_string(&aliases_path)?;
-    let mut aliases = Vec::new();
+    Ok(aliases_by_category()?
+        .remove(&Some(category_name.to_string()))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|alias| (alias.name, alias.command))
+        .collect())
+}
 
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
+const COLOR_NAMES: &[&str] = &[
+    "black", "red", "green", "yellow", "blue", "magenta", "purple", "cyan", "white", "grey",
+    "gray", "orange",
+];
+
+fn parse_color(color: &str) -> anyhow::Result<Color> {
+    let value = color.trim().to_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+            return Ok(Color::Rgb {
+                r: channel(0)?,
+                g: channel(2)?,
+                b: channel(4)?,
+            });
         }
+    }
 
-        if let Some((name, command, _note, tags)) = parse_alias_line(line) {
-            if tags
-                .iter()
-                .any(|tag| tag == &format!("category:{category_name}"))
-            {
-                aliases.push((name, command));
-            }
-        }
+    Ok(match value.as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" | "purple" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        "orange" => Color::Rgb {
+            r: 255,
+            g: 165,
+            b: 0,
+        },
+        _ => anyhow::bail!(
+            "Unsupported color: {}. Supported: {}, or a hex code like #ff8800",
+            color,
+            COLOR_NAMES.join(", ")
+        ),
+    })
+}
+
+fn styled_name(category: &Category, use_color: bool) -> String {
+    match category.color.as_deref().map(parse_color) {
+        Some(Ok(color)) if use_color => category.name.as_str().with(color).to_string(),
+        _ => category.name.clone(),
     }
+}
 
-    Ok(aliases)
+fn color_enabled() -> bool {
+    load_config().display.color_output && io::stdout().is_terminal()
 }
 
 fn display_category_tree(categories: &[Category]) -> anyhow::Result<()> {
@@ -435,20 +691,29 @@ fn display_category_tree(categories: &[Category]) -> anyhow::Result<()> {
 
     let root_categories: Vec<_> = categories.iter().filter(|c| c.parent.is_none()).collect();
 
+    let use_color = color_enabled();
     for root in root_categories {
-        display_category_node(root, categories, 0);
+        display_category_node(root, categories, 0, use_color);
     }
 
     Ok(())
 }
 
-fn display_category_node(category: &Category, all_categories: &[Category], depth: usize) {
+fn display_category_node(
+    category: &Category,
+    all_categories: &[Category],
+    depth: usize,
+    use_color: bool,
+) {
     let indent = "  ".repeat(depth);
     let icon = category.icon.as_deref().unwrap_or("[FOLDER]");
 
     println!(
         "{}{} {} ({} aliases)",
-        indent, icon, category.name, category.alias_count
+        indent,
+        icon,
+        styled_name(category, use_color),
+        category.alias_count
     );
 
     let children: Vec<_> = all_categories
@@ -457,17 +722,32 @@ fn display_category_node(category: &Category, all_categories: &[Category], depth
         .collect();
 
     for child in children {
-        display_category_node(child, all_categories, depth + 1);
+        display_category_node(child, all_categories, depth + 1, use_color);
     }
 }
 
+fn build_category_tree(categories: &[Category], parent: Option<&str>) -> Vec<CategoryNode> {
+    categories
+        .iter()
+        .filter(|c| c.parent.as_deref() == parent)
+        .map(|c| CategoryNode {
+            name: c.name.clone(),
+            alias_count: c.alias_count,
+            icon: c.icon.clone(),
+            color: c.color.clone(),
+            children: build_category_tree(categories, Some(&c.name)),
+        })
+        .collect()
+}
+
 fn display_category_list(categories: &[Category], show_counts: bool) -> anyhow::Result<()> {
     println!("Categories:\n");
 
+    let use_color = color_enabled();
     for category in categories {
         let icon = category.icon.as_deref().unwrap_or("[FOLDER]");
 
-        print!("{} {}", icon, category.name);
+        print!("{} {}", icon, styled_name(category, use_color));
 
         if let Some(parent) = &category.parent {
             print!(" (child of {parent})");
@@ -489,115 +769,35 @@ fn display_category_list(categories: &[Category], show_counts: bool) -> anyhow::
     Ok(())
 }
 
-fn parse_alias_line(line: &str) -> Option<(String, String, Option<String>, Vec<String>)> {
-    if !line.starts_with("alias ") {
-        return None;
-    }
-
-    let eq_pos = line.find('=')?;
-    let name = line[6..eq_pos].trim().to_string();
-    let rest = &line[eq_pos + 1..];
-
-    let mut command = String::new();
-    let mut remaining = "";
-
-    let rest = rest.trim();
-    if let Some(stripped) = rest.strip_prefix('\'') {
-        if let Some(end_quote) = stripped.find('\'') {
-            command = rest[1..end_quote + 1].to_string();
-            remaining = &rest[end_quote + 2..];
-        }
-    } else if let Some(stripped) = rest.strip_prefix('"') {
-        if let Some(end_quote) = stripped.find('"') {
-            command = rest[1..end_quote + 1].to_string();
-            remaining = &rest[end_quote + 2..];
-        }
-    } else if let Some(hash_pos) = rest.find('#') {
-        command = rest[..hash_pos].trim().to_string();
-        remaining = &rest[hash_pos..];
-    } else {
-        command = rest.to_string();
-    }
-
-    let mut note = None;
-    let mut tags = Vec::new();
-
-    if let Some(tags_pos) = remaining.find("#tags:") {
-        let tags_part = &remaining[tags_pos + 6..];
-        tags = tags_part.split(',').map(|s| s.trim().to_string()).collect();
-
-        let note_part = remaining[..tags_pos].trim();
-        if let Some(stripped) = note_part.strip_prefix('#') {
-            let note_text = stripped.trim();
-            if !note_text.is_empty() {
-                note = Some(note_text.to_string());
-            }
-        }
-    } else if remaining.trim().starts_with('#') {
-        let comment_text = remaining.trim()[1..].trim();
-        if comment_text.contains("#tags:") {
-            if let Some(tags_pos) = comment_text.find("#tags:") {
-                let note_part = comment_text[..tags_pos].trim();
-                if !note_part.is_empty() {
-                    note = Some(note_part.to_string());
-                }
-                let tags_part = &comment_text[tags_pos + 6..];
-                tags = tags_part.split(',').map(|s| s.trim().to_string()).collect();
-            }
-        } else if !comment_text.is_empty() {
-            note = Some(comment_text.to_string());
-        }
-    }
-
-    Some((name, command, note, tags))
-}
-
-fn build_alias_line(name: &str, command: &str, note: Option<&str>, tags: &[String]) -> String {
-    let mut line = format!("alias {name}='{command}'");
-
-    let mut comment_parts = Vec::new();
-
-    if let Some(note_text) = note {
-        comment_parts.push(note_text.to_string());
-    }
-
-    if !tags.is_empty() {
-        comment_parts.push(format!("#tags:{}", tags.join(",")));
-    }
-
-    if !comment_parts.is_empty() {
-        line.push_str(&format!(" # {}", comment_parts.join(" ")));
-    }
-
-    line
-}
-
 fn analyze_command_patterns(aliases: &[(String, String, Option<String>)]) -> Vec<(String, usize)> {
     let mut patterns: HashMap<String, usize> = HashMap::new();
 
     for (_, command, _) in aliases {
-        let first_word = command.split_whitespace().next().unwrap_or(command);
-
-        let pattern = match first_word {
-            cmd if cmd.starts_with("git") => "git",
-            "docker" | "docker-compose" => "docker",
-            "npm" | "yarn" | "pnpm" => "nodejs",
-            "kubectl" | "k8s" => "kubernetes",
-            "ssh" | "scp" | "rsync" => "network",
-            "ls" | "ll" | "la" | "dir" => "listing",
-            "cd" | "pushd" | "popd" => "navigation",
-            "cat" | "less" | "more" | "head" | "tail" => "viewing",
-            _ => "general",
-        };
-
+        let pattern = command_pattern(command);
         *patterns.entry(pattern.to_string()).or_insert(0) += 1;
     }
 
     let mut pattern_vec: Vec<_> = patterns.into_iter().collect();
-    pattern_vec.sort_by(|a, b| b.1.cmp(&a.1));
+    pattern_vec.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
     pattern_vec
 }
 
+pub fn command_pattern(command: &str) -> &'static str {
+    let first_word = command.split_whitespace().next().unwrap_or(command);
+
+    match first_word {
+        cmd if cmd.starts_with("git") => "git",
+        "docker" | "docker-compose" => "docker",
+        "npm" | "yarn" | "pnpm" => "nodejs",
+        "kubectl" | "k8s" => "kubernetes",
+        "ssh" | "scp" | "rsync" => "network",
+        "ls" | "ll" | "la" | "dir" => "listing",
+        "cd" | "pushd" | "popd" => "navigation",
+        "cat" | "less" | "more" | "head" | "tail" => "viewing",
+        _ => "general",
+    }
+}
+
 fn create_default_categories() -> Vec<Category> {
     let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
 
@@ -649,3 +849,204 @@ fn create_default_categories() -> Vec<Category> {
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+
+    #[test]
+    fn category_names_with_spaces_are_rejected() {
+        assert!(validate_category_name("git").is_ok());
+        assert!(validate_category_name("git-tools").is_ok());
+        assert!(validate_category_name("Git Tools").is_err());
+        assert!(validate_category_name("a#b").is_err());
+        assert!(validate_category_name("").is_err());
+    }
+
+    fn category(name: &str, parent: Option<&str>, created_at: &str) -> Category {
+        Category {
+            name: name.to_string(),
+            description: String::new(),
+            parent: parent.map(str::to_string),
+            color: None,
+            icon: None,
+            created_at: created_at.to_string(),
+            alias_count: 0,
+        }
+    }
+
+    #[test]
+    fn count_sort_orders_by_alias_count_then_name() {
+        let home = FakeHome::new("category-sort");
+        home.write(
+            "aliases",
+            "alias gs='git status' #category:git\n\
+             alias gp='git push' #category:git\n\
+             alias dps='docker ps' #category:docker\n\
+             alias ll='ls -la'\n\
+             alias kc='kubectl' #category:k8s\n",
+        );
+        let mut categories = vec![
+            category("misc", None, "2024-01-01 00:00:00"),
+            category("k8s", None, "2024-01-02 00:00:00"),
+            category("git", None, "2024-01-03 00:00:00"),
+            category("docker", None, "2024-01-04 00:00:00"),
+        ];
+
+        update_alias_counts(&mut categories).unwrap();
+        sort_categories(&mut categories, &CategorySort::Count);
+
+        let order: Vec<(&str, usize)> = categories
+            .iter()
+            .map(|c| (c.name.as_str(), c.alias_count))
+            .collect();
+        assert_eq!(order, [("git", 2), ("docker", 1), ("k8s", 1), ("misc", 0)]);
+    }
+
+    #[test]
+    fn recursive_count_includes_child_categories() {
+        let home = FakeHome::new("category-recursive");
+        home.write(
+            "aliases",
+            "alias dev='cd ~/dev' #category:work\n\
+             alias gs='git status' #category:git\n\
+             alias gp='git push' #category:git\n\
+             alias dps='docker ps' #category:docker\n\
+             alias ll='ls -la'\n",
+        );
+        let categories = vec![
+            category("work", None, "2024-01-01 00:00:00"),
+            category("git", Some("work"), "2024-01-02 00:00:00"),
+            category("docker", Some("work"), "2024-01-03 00:00:00"),
+        ];
+
+        assert_eq!(recursive_alias_count(&categories, "work").unwrap(), 4);
+        assert_eq!(recursive_alias_count(&categories, "git").unwrap(), 2);
+    }
+
+    #[test]
+    fn moving_a_function_block_keeps_the_file_well_formed() {
+        let home = FakeHome::new("category-move");
+        let aliases = home.write(
+            "aliases",
+            "deploy() { # ship it\n    cargo build\n    scp app host:\n}\nalias ll='ls -la'\n",
+        );
+
+        move_alias_to_category("deploy", "system").unwrap();
+
+        let content = fs::read_to_string(&aliases).unwrap();
+        assert!(content.ends_with("alias ll='ls -la'\n"), "{content}");
+        let moved = parse_content(&content);
+        assert_eq!(moved.len(), 2);
+        assert_eq!(moved[0].name, "deploy");
+        assert_eq!(moved[0].command, "cargo build\nscp app host:");
+        assert_eq!(moved[0].category.as_deref(), Some("system"));
+    }
+
+    #[test]
+    fn tree_json_nests_children_under_their_parent() {
+        let mut categories = vec![
+            category("work", None, "2024-01-01 00:00:00"),
+            category("git", Some("work"), "2024-01-02 00:00:00"),
+            category("docker", Some("work"), "2024-01-03 00:00:00"),
+        ];
+        categories[1].alias_count = 2;
+
+        let tree = serde_json::to_value(build_category_tree(&categories, None)).unwrap();
+
+        let node = |name: &str, alias_count: usize, children: serde_json::Value| {
+            serde_json::json!({
+                "name": name,
+                "alias_count": alias_count,
+                "icon": null,
+                "color": null,
+                "children": children,
+            })
+        };
+        assert_eq!(
+            tree,
+            serde_json::json!([node(
+                "work",
+                0,
+                serde_json::json!([
+                    node("git", 2, serde_json::json!([])),
+                    node("docker", 0, serde_json::json!([]))
+                ])
+            )])
+        );
+    }
+
+    #[test]
+    fn grouped_sections_are_sorted_with_uncategorized_last() {
+        let home = FakeHome::new("category-group");
+        home.write(
+            "aliases",
+            "alias ll='ls -la'\n\
+             alias kc='kubectl' #category:k8s\n\
+             alias gp='git push' #category:git\n\
+             alias dps='docker ps' #category:docker\n\
+             alias gs='git status' #category:git\n",
+        );
+        let categories = vec![
+            category("k8s", None, "2024-01-01 00:00:00"),
+            category("git", None, "2024-01-02 00:00:00"),
+            category("docker", None, "2024-01-03 00:00:00"),
+        ];
+
+        let mut out = Vec::new();
+        write_grouped_aliases(&categories, &mut out).unwrap();
+
+        let report = String::from_utf8(out).unwrap();
+        let sections: Vec<&str> = report
+            .lines()
+            .filter(|line| line.ends_with(" aliases):"))
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                "docker (1 aliases):",
+                "git (2 aliases):",
+                "k8s (1 aliases):",
+                "Uncategorized (1 aliases):"
+            ]
+        );
+        let git_aliases: Vec<&str> = report
+            .lines()
+            .skip_while(|line| !line.starts_with("git ("))
+            .skip(1)
+            .take(2)
+            .collect();
+        assert_eq!(git_aliases, ["  • gp → git push", "  • gs → git status"]);
+    }
+
+    #[test]
+    fn category_json_lists_aliases_and_children() {
+        let home = FakeHome::new("category-json");
+        home.write(
+            "aliases",
+            "alias dev='cd ~/dev' #category:work\n\
+             alias gs='git status' #category:git\n\
+             alias vpn='sudo openvpn' #category:work\n",
+        );
+        let categories = vec![
+            category("work", None, "2024-01-01 00:00:00"),
+            category("git", Some("work"), "2024-01-02 00:00:00"),
+            category("docker", Some("work"), "2024-01-03 00:00:00"),
+        ];
+
+        let details = category_details(&categories, &categories[0], None).unwrap();
+
+        let json = serde_json::to_value(details).unwrap();
+        assert_eq!(json["name"], "work");
+        assert_eq!(json["children"], serde_json::json!(["git", "docker"]));
+        assert_eq!(
+            json["aliases"],
+            serde_json::json!([
+                {"name": "dev", "command": "cd ~/dev"},
+                {"name": "vpn", "command": "sudo openvpn"},
+            ])
+        );
+        assert!(json.get("total_alias_count").is_none());
+    }
+}
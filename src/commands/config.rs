@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static PARSE_WARNING_SHOWN: AtomicBool = AtomicBool::new(false);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -8,6 +15,10 @@ pub struct Config {
     pub search: SearchConfig,
     pub aliases: AliasConfig,
     pub update: UpdateConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub plugins: PluginsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +49,20 @@ pub struct AliasConfig {
     pub file_path: String,
     pub sort_on_add: bool,
     pub validate_on_add: bool,
+    #[serde(default)]
+    pub extra_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutputConfig {
+    #[serde(default)]
+    pub default_output_dir: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginsConfig {
+    #[serde(default)]
+    pub timeout_secs: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +98,7 @@ impl Default for Config {
                 file_path: "~/.shorty/aliases".to_string(),
                 sort_on_add: false,
                 validate_on_add: true,
+                extra_files: Vec::new(),
             },
             update: UpdateConfig {
                 enabled: true,
@@ -81,12 +107,29 @@ impl Default for Config {
                 backup_old_versions: true,
                 max_backups: 3,
             },
+            output: OutputConfig::default(),
+            plugins: PluginsConfig::default(),
         }
     }
 }
 
 impl Config {
     pub fn load() -> anyhow::Result<Self> {
+        match Self::load_strict() {
+            Err(e) if e.is::<toml::de::Error>() => {
+                if !PARSE_WARNING_SHOWN.swap(true, Ordering::Relaxed) {
+                    eprintln!(
+                        "Warning: could not parse {}; using default settings. Run 'shorty config validate' for details.",
+                        get_config_path()?.display()
+                    );
+                }
+                Ok(Self::default())
+            }
+            result => result,
+        }
+    }
+
+    pub(crate) fn load_strict() -> anyhow::Result<Self> {
         let config_path = get_config_path()?;
 
         if config_path.exists() {
@@ -132,6 +175,7 @@ impl Config {
             "aliases.file_path" => Some(self.aliases.file_path.clone()),
             "aliases.sort_on_add" => Some(self.aliases.sort_on_add.to_string()),
             "aliases.validate_on_add" => Some(self.aliases.validate_on_add.to_string()),
+            "aliases.extra_files" => Some(self.aliases.extra_files.join(",")),
 
             "update.enabled" => Some(self.update.enabled.to_string()),
             "update.check_interval_hours" => Some(self.update.check_interval_hours.to_string()),
@@ -139,6 +183,10 @@ impl Config {
             "update.backup_old_versions" => Some(self.update.backup_old_versions.to_string()),
             "update.max_backups" => Some(self.update.max_backups.to_string()),
 
+            "output.default_output_dir" => Some(self.output.default_output_dir.clone()),
+
+            "plugins.timeout_secs" => Some(self.plugins.timeout_secs.to_string()),
+
             _ => None,
         }
     }
@@ -190,6 +238,13 @@ impl Config {
             "aliases.validate_on_add" => {
                 self.aliases.validate_on_add = parse_bool(value)?;
             }
+            "aliases.extra_files" => {
+                self.aliases.extra_files = value
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+            }
 
             "update.enabled" => {
                 self.update.enabled = parse_bool(value)?;
@@ -207,6 +262,14 @@ impl Config {
                 self.update.max_backups = value.parse()?;
             }
 
+            "output.default_output_dir" => {
+                self.output.default_output_dir = value.to_string();
+            }
+
+            "plugins.timeout_secs" => {
+                self.plugins.timeout_secs = value.parse()?;
+            }
+
             _ => {
                 anyhow::bail!("Unknown configuration key: {}", key);
             }
@@ -273,6 +336,10 @@ impl Config {
                 "aliases.validate_on_add".to_string(),
                 "Validate aliases when adding new ones".to_string(),
             ),
+            (
+                "aliases.extra_files".to_string(),
+                "Comma-separated list of additional read-only aliases files".to_string(),
+            ),
             (
                 "update.enabled".to_string(),
                 "Enable automatic update checking".to_string(),
@@ -293,29 +360,74 @@ impl Config {
                 "update.max_backups".to_string(),
                 "Maximum number of binary backups to keep".to_string(),
             ),
+            (
+                "output.default_output_dir".to_string(),
+                "Directory for generated files such as exports and completion scripts".to_string(),
+            ),
+            (
+                "plugins.timeout_secs".to_string(),
+                "Seconds before a running plugin command is killed (0 = no limit)".to_string(),
+            ),
         ]
     }
 }
 
 pub fn set_config(key: &str, value: &str) -> anyhow::Result<()> {
-    let mut config = Config::load()?;
+    let mut config = match Config::load_strict() {
+        Err(e) if e.is::<toml::de::Error>() => anyhow::bail!(
+            "Cannot update configuration: {} could not be parsed. Run 'shorty config validate' for details",
+            get_config_path()?.display()
+        ),
+        result => result?,
+    };
     config.set_value(key, value)?;
     config.save()?;
 
     println!("Configuration updated: {key} = {value}");
+    if key == "aliases.file_path" {
+        let aliases_path = crate::utils::get_aliases_path()?;
+        println!(
+            "Update your shell configuration to 'source {}'",
+            aliases_path.display()
+        );
+    }
     Ok(())
 }
 
 pub fn get_config(key: &str) -> anyhow::Result<()> {
     let config = Config::load()?;
+    write_config_value(&mut io::stdout().lock(), &config, key)?;
+    Ok(())
+}
 
+fn write_config_value(out: &mut impl Write, config: &Config, key: &str) -> io::Result<()> {
     if let Some(value) = config.get_value(key) {
-        println!("{key} = {value}");
+        return writeln!(out, "{key} = {value}");
+    }
+
+    let prefix = if key.ends_with('.') {
+        key.to_string()
     } else {
-        println!("Unknown configuration key: {key}");
-        println!("\nAvailable keys:");
+        format!("{key}.")
+    };
+    let section_keys: Vec<String> = config
+        .get_all_keys()
+        .into_iter()
+        .map(|(k, _)| k)
+        .filter(|k| k.starts_with(&prefix))
+        .collect();
+
+    if !section_keys.is_empty() {
+        for k in section_keys {
+            if let Some(value) = config.get_value(&k) {
+                writeln!(out, "{k} = {value}")?;
+            }
+        }
+    } else {
+        writeln!(out, "Unknown configuration key: {key}")?;
+        writeln!(out, "\nAvailable keys:")?;
         for (k, description) in config.get_all_keys() {
-            println!("  {k} - {description}");
+            writeln!(out, "  {k} - {description}")?;
         }
     }
 
@@ -360,14 +472,33 @@ pub fn list_config() -> anyhow::Result<()> {
     println!("  file_path           = {}", config.aliases.file_path);
     println!("  sort_on_add         = {}", config.aliases.sort_on_add);
     println!("  validate_on_add     = {}", config.aliases.validate_on_add);
+    println!(
+        "  extra_files         = {}",
+        config.aliases.extra_files.join(",")
+    );
 
     println!("\nUpdate:");
     println!("  enabled             = {}", config.update.enabled);
-    println!("  check_interval_hours= {}", config.update.check_interval_hours);
+    println!(
+        "  check_interval_hours= {}",
+        config.update.check_interval_hours
+    );
     println!("  auto_download       = {}", config.update.auto_download);
-    println!("  backup_old_versions = {}", config.update.backup_old_versions);
+    println!(
+        "  backup_old_versions = {}",
+        config.update.backup_old_versions
+    );
     println!("  max_backups         = {}", config.update.max_backups);
 
+    println!("\nOutput:");
+    println!(
+        "  default_output_dir  = {}",
+        config.output.default_output_dir
+    );
+
+    println!("\nPlugins:");
+    println!("  timeout_secs        = {}", config.plugins.timeout_secs);
+
     println!("\nUse 'shorty config set <key> <value>' to change settings");
 
     Ok(())
@@ -390,6 +521,105 @@ pub fn reset_config() -> anyhow::Result<()> {
     Ok(())
 }
 
+pub fn validate_config() -> anyhow::Result<bool> {
+    let config_path = get_config_path()?;
+
+    if !config_path.exists() {
+        println!(
+            "No configuration file at {}; defaults are used",
+            config_path.display()
+        );
+        return Ok(true);
+    }
+
+    let content = fs::read_to_string(&config_path)?;
+    let table: toml::Table = match content.parse() {
+        Ok(table) => table,
+        Err(e) => {
+            println!("{}: parse error", config_path.display());
+            println!("  {}", describe_toml_error(&content, &e));
+            return Ok(false);
+        }
+    };
+
+    let problems = config_problems(&content, &table);
+    if problems.is_empty() {
+        println!("{} is valid", config_path.display());
+        return Ok(true);
+    }
+
+    println!(
+        "{}: {} problem(s) found",
+        config_path.display(),
+        problems.len()
+    );
+    for problem in &problems {
+        println!("  {problem}");
+    }
+
+    Ok(false)
+}
+
+fn config_problems(content: &str, table: &toml::Table) -> Vec<String> {
+    let known_keys: Vec<String> = Config::default()
+        .get_all_keys()
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    let mut problems = Vec::new();
+
+    for (section, value) in table {
+        let Some(fields) = value.as_table() else {
+            problems.push(format!("Unknown key: {section}"));
+            continue;
+        };
+        for (field, value) in fields {
+            let key = format!("{section}.{field}");
+            if !known_keys.contains(&key) {
+                problems.push(format!("Unknown key: {key}"));
+                continue;
+            }
+            if let Err(e) = Config::default().set_value(&key, &toml_value_string(value)) {
+                problems.push(format!("Invalid value for {key}: {e}"));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        if let Err(e) = toml::from_str::<Config>(content) {
+            problems.push(describe_toml_error(content, &e));
+        }
+    }
+
+    problems
+}
+
+fn describe_toml_error(content: &str, error: &toml::de::Error) -> String {
+    let message = error.message().trim().replace('\n', " ");
+    match error.span() {
+        Some(span) => {
+            let line = content[..span.start.min(content.len())]
+                .lines()
+                .count()
+                .max(1);
+            format!("line {line}: {message}")
+        }
+        None => message,
+    }
+}
+
+fn toml_value_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(toml_value_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
 fn get_config_path() -> anyhow::Result<PathBuf> {
     let home_dir =
         dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
@@ -408,7 +638,56 @@ fn parse_bool(value: &str) -> anyhow::Result<bool> {
     }
 }
 
-#[allow(dead_code)]
 pub fn load_config() -> Config {
     Config::load().unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(content: &str) -> Vec<String> {
+        config_problems(content, &content.parse().unwrap())
+    }
+
+    #[test]
+    fn invalid_bool_is_reported() {
+        let problems = problems("[display]\ncolor_output = \"maybe\"\n");
+        assert_eq!(problems.len(), 1);
+        assert!(
+            problems[0].starts_with("Invalid value for display.color_output:"),
+            "{problems:?}"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let problems = problems("[display]\ncolour_output = true\n\n[extras]\nflag = 1\n");
+        assert_eq!(
+            problems,
+            [
+                "Unknown key: display.colour_output",
+                "Unknown key: extras.flag"
+            ]
+        );
+    }
+
+    #[test]
+    fn section_prefix_prints_every_key_in_the_section() {
+        let config = Config::default();
+
+        for key in ["search.", "search"] {
+            let mut out = Vec::new();
+            write_config_value(&mut out, &config, key).unwrap();
+
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                "search.fuzzy_matching = false\n\
+                 search.case_sensitive = false\n\
+                 search.search_in_notes = true\n\
+                 search.search_in_tags = true\n",
+                "{key}"
+            );
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use crate::commands::config::Config;
+use crate::utils::get_aliases_path;
+use chrono::Local;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+enum Problem {
+    MissingDir(PathBuf),
+    CorruptConfig(PathBuf, String),
+    MissingAliasesFile(PathBuf),
+    MissingShellIntegration(PathBuf, String),
+}
+
+impl Problem {
+    fn description(&self) -> String {
+        match self {
+            Problem::MissingDir(dir) => format!("Directory {} does not exist", dir.display()),
+            Problem::CorruptConfig(path, error) => {
+                format!("Config file {} cannot be parsed: {error}", path.display())
+            }
+            Problem::MissingAliasesFile(path) => {
+                format!("Aliases file {} does not exist", path.display())
+            }
+            Problem::MissingShellIntegration(rc_file, _) => {
+                format!("{} does not source your aliases", rc_file.display())
+            }
+        }
+    }
+
+    fn fix(&self) -> anyhow::Result<String> {
+        match self {
+            Problem::MissingDir(dir) => {
+                fs::create_dir_all(dir)?;
+                Ok(format!("Created {}", dir.display()))
+            }
+            Problem::CorruptConfig(path, _) => {
+                let backup_path = path
+                    .with_extension(format!("toml.{}.bak", Local::now().format("%Y%m%d_%H%M%S")));
+                fs::copy(path, &backup_path)?;
+                Config::default().save()?;
+                Ok(format!(
+                    "Reset {} to defaults (previous file saved as {})",
+                    path.display(),
+                    backup_path.display()
+                ))
+            }
+            Problem::MissingAliasesFile(path) => {
+                if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, "")?;
+                Ok(format!("Created empty aliases file {}", path.display()))
+            }
+            Problem::MissingShellIntegration(rc_file, source_line) => {
+                if let Some(parent) = rc_file.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(rc_file)?;
+                writeln!(file, "\n# Load aliases from shorty\n{source_line}")?;
+                Ok(format!("Added '{source_line}' to {}", rc_file.display()))
+            }
+        }
+    }
+}
+
+pub fn run_doctor(fix: bool) -> anyhow::Result<()> {
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    let shorty_dir = home_dir.join(".shorty");
+
+    println!("Checking shorty setup...\n");
+
+    let mut problems = 0;
+    let mut fixed = 0;
+    let mut handle = |problem: Option<Problem>, ok: String| -> anyhow::Result<()> {
+        let Some(problem) = problem else {
+            println!("[OK]      {ok}");
+            return Ok(());
+        };
+        problems += 1;
+        println!("[PROBLEM] {}", problem.description());
+        if fix {
+            let action = problem.fix()?;
+            fixed += 1;
+            println!("[FIXED]   {action}");
+        }
+        Ok(())
+    };
+
+    handle(
+        (!shorty_dir.is_dir()).then(|| Problem::MissingDir(shorty_dir.clone())),
+        format!("Directory {} exists", shorty_dir.display()),
+    )?;
+
+    let config_path = shorty_dir.join("config.toml");
+    let config_error = fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|content| toml::from_str::<Config>(&content).err());
+    handle(
+        config_error.map(|e| {
+            Problem::CorruptConfig(config_path.clone(), e.message().trim().replace('\n', " "))
+        }),
+        if config_path.exists() {
+            "Config file is valid".to_string()
+        } else {
+            "No config file; defaults are used".to_string()
+        },
+    )?;
+
+    let aliases_path = if shorty_dir.is_dir() {
+        get_aliases_path()?
+    } else {
+        shorty_dir.join("aliases")
+    };
+    handle(
+        (!aliases_path.exists()).then(|| Problem::MissingAliasesFile(aliases_path.clone())),
+        format!("Aliases file {} exists", aliases_path.display()),
+    )?;
+
+    match shell_integration(&home_dir, &aliases_path) {
+        Some((rc_file, source_line)) => {
+            let integrated = fs::read_to_string(&rc_file)
+                .is_ok_and(|content| sources_aliases(&content, &home_dir, &aliases_path));
+            handle(
+                (!integrated)
+                    .then(|| Problem::MissingShellIntegration(rc_file.clone(), source_line)),
+                format!("{} sources your aliases", rc_file.display()),
+            )?;
+        }
+        None => println!(
+            "[WARN]    Unsupported shell; add 'source {}' to your shell configuration manually",
+            aliases_path.display()
+        ),
+    }
+
+    println!();
+    if problems == 0 {
+        println!("No problems found.");
+    } else if fix {
+        println!("Fixed {fixed} of {problems} problem(s).");
+    } else {
+        println!("Found {problems} problem(s). Run 'shorty doctor --fix' to repair them.");
+    }
+
+    Ok(())
+}
+
+fn display_path(home_dir: &Path, path: &Path) -> String {
+    match path.strip_prefix(home_dir) {
+        Ok(relative) => format!("~/{}", relative.display()),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+fn shell_integration(home_dir: &Path, aliases_path: &Path) -> Option<(PathBuf, String)> {
+    let shell = std::env::var("SHELL").ok()?;
+    let shell_name = Path::new(&shell).file_name()?.to_string_lossy().to_string();
+    let path = display_path(home_dir, aliases_path);
+
+    match shell_name.as_str() {
+        "bash" => Some((home_dir.join(".bashrc"), format!("source {path}"))),
+        "zsh" => Some((home_dir.join(".zshrc"), format!("source {path}"))),
+        "fish" => Some((
+            home_dir.join(".config").join("fish").join("config.fish"),
+            format!("test -f {path}; and source {path}"),
+        )),
+        _ => None,
+    }
+}
+
+fn sources_aliases(content: &str, home_dir: &Path, aliases_path: &Path) -> bool {
+    let full = aliases_path.display().to_string();
+    let short = display_path(home_dir, aliases_path);
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .any(|line| line.contains(&full) || line.contains(&short))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+
+    #[test]
+    fn fix_creates_the_missing_directory_and_aliases_file() {
+        let home = FakeHome::new("doctor-missing");
+        let shorty_dir = home.path().join(".shorty");
+        assert!(!shorty_dir.exists());
+
+        run_doctor(true).unwrap();
+
+        assert!(shorty_dir.is_dir());
+        assert_eq!(fs::read_to_string(shorty_dir.join("aliases")).unwrap(), "");
+    }
+
+    #[test]
+    fn fix_leaves_an_existing_aliases_file_alone() {
+        let home = FakeHome::new("doctor-existing");
+        let aliases = home.write("aliases", "alias gs='git status'\n");
+
+        run_doctor(true).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(aliases).unwrap(),
+            "alias gs='git status'\n"
+        );
+    }
+
+    #[test]
+    fn remediations_describe_what_they_did() {
+        let home = FakeHome::new("doctor-remediations");
+        let dir = home.path().join("nested").join(".shorty");
+        let file = home.path().join("other").join("aliases");
+
+        let created_dir = Problem::MissingDir(dir.clone()).fix().unwrap();
+        assert_eq!(created_dir, format!("Created {}", dir.display()));
+        assert!(dir.is_dir());
+
+        let created_file = Problem::MissingAliasesFile(file.clone()).fix().unwrap();
+        assert_eq!(
+            created_file,
+            format!("Created empty aliases file {}", file.display())
+        );
+        assert_eq!(fs::read_to_string(file).unwrap(), "");
+    }
+}
@@ -1,6 +1,24 @@
-use crate::utils::get_aliases_path;
-use std::fs;
-use std::io::Write;
+use crate::alias::{parse_entries, Alias, AliasEntry};
+use crate::commands::backup::backup_before_edit;
+use crate::commands::history::record_operation;
+use crate::commands::plugins::run_alias_hooks;
+use crate::utils::{acquire_lock, get_aliases_path, read_aliases_file, write_aliases_atomic};
+
+fn apply_edit(
+    mut existing: Alias,
+    new_command: &str,
+    new_note: &Option<String>,
+    new_tags: &[String],
+) -> Alias {
+    existing.command = new_command.to_string();
+    if let Some(note) = new_note {
+        existing.note = Some(note.clone());
+    }
+    if !new_tags.is_empty() {
+        existing.tags = new_tags.to_vec();
+    }
+    existing
+}
 
 pub fn edit_alias(
     alias: &str,
@@ -8,60 +26,48 @@ pub fn edit_alias(
     new_note: &Option<String>,
     new_tags: &[String],
 ) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
-
-    let contents = fs::read_to_string(&aliases_path)?;
-    let mut new_contents: Vec<String> = Vec::new();
-    let mut alias_found = false;
+    let aliases_path = get_aliases_path()?;
 
-    for line in contents.lines() {
-        if line.starts_with(&format!("alias {alias}=")) {
-            alias_found = true;
-
-            let existing_note = line
-                .split('#')
-                .nth(1)
-                .map(|s| s.trim().to_string())
-                .unwrap_or_default();
-            let existing_tags = line
-                .split('#')
-                .nth(2)
-                .map(|s| s.trim().to_string())
-                .unwrap_or_default();
-
-            let note_comment = if let Some(note) = new_note {
-                format!(" # {note}")
-            } else if !existing_note.is_empty() {
-                format!(" # {existing_note}")
-            } else {
-                String::new()
-            };
+    let contents = read_aliases_file(&aliases_path)?;
+    let Some(existing) = parse_entries(&contents)
+        .into_iter()
+        .find(|entry| entry.alias.name == alias)
+    else {
+        println!("Alias '{alias}' not found.");
+        return Ok(());
+    };
+    let edited = apply_edit(existing.alias, new_command, new_note, new_tags);
 
-            let tags_str = if !new_tags.is_empty() {
-                format!(" #tags:{}", new_tags.join(","))
-            } else if !existing_tags.is_empty() {
-                format!(" #tags:{existing_tags}")
-            } else {
-                String::new()
-            };
+    run_alias_hooks("pre_edit", &edited)?;
 
-            new_contents.push(format!(
-                "alias {alias}='{new_command}'{note_comment}{tags_str}"
-            ));
-        } else {
-            new_contents.push(line.to_string());
-        }
-    }
+    let lock = acquire_lock()?;
 
-    if !alias_found {
+    let contents = read_aliases_file(&aliases_path)?;
+    let Some(entry) = parse_entries(&contents)
+        .into_iter()
+        .find(|entry| entry.alias.name == alias)
+    else {
         println!("Alias '{alias}' not found.");
         return Ok(());
-    }
+    };
 
-    let mut file = fs::File::create(&aliases_path)?;
-    for line in new_contents {
-        writeln!(file, "{line}")?;
+    let replacement = AliasEntry {
+        alias: apply_edit(entry.alias, new_command, new_note, new_tags),
+        ..entry
     }
+    .to_line();
+    let mut new_contents: Vec<String> = contents.lines().map(str::to_string).collect();
+    let start = entry.line_number - 1;
+    new_contents.splice(start..start + entry.line_count, [replacement]);
+
+    let mut output = new_contents.join("\n");
+    output.push('\n');
+    backup_before_edit()?;
+    write_aliases_atomic(&output)?;
+    drop(lock);
+
+    record_operation("edit", &format!("{alias} -> {new_command}"));
+    run_alias_hooks("post_edit", &edited)?;
 
     println!("Edited alias: {alias} -> {new_command}");
     println!("To apply the changes, please restart your terminal!");
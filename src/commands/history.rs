@@ -0,0 +1,147 @@
+use crate::utils::{write_atomic, OutputFormat};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HistoryEntry {
+    timestamp: String,
+    operation: String,
+    details: String,
+}
+
+pub fn record_operation(operation: &str, details: &str) {
+    if let Err(e) = append_entry(operation, details) {
+        eprintln!("Warning: Could not record history entry: {e}");
+    }
+}
+
+pub fn show_history(limit: usize, output: OutputFormat) -> anyhow::Result<()> {
+    let history_path = get_history_path()?;
+
+    if !history_path.exists() && output == OutputFormat::Json {
+        println!("[]");
+        return Ok(());
+    }
+
+    if !history_path.exists() {
+        println!("No history recorded yet.");
+        return Ok(());
+    }
+
+    let entries = read_history(&history_path)?;
+
+    let start = entries.len().saturating_sub(limit);
+    let shown = &entries[start..];
+
+    if output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(shown)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No history recorded yet.");
+        return Ok(());
+    }
+
+    println!("Showing {} of {} operation(s):", shown.len(), entries.len());
+    for entry in shown {
+        println!(
+            "{:<20} {:<8} {}",
+            entry.timestamp, entry.operation, entry.details
+        );
+    }
+
+    Ok(())
+}
+
+fn read_history(history_path: &Path) -> anyhow::Result<Vec<HistoryEntry>> {
+    let content = fs::read_to_string(history_path)?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn append_entry(operation: &str, details: &str) -> anyhow::Result<()> {
+    let history_path = get_history_path()?;
+
+    if let Some(parent) = history_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let entry = HistoryEntry {
+        timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        operation: operation.to_string(),
+        details: details.to_string(),
+    };
+    let line = serde_json::to_string(&entry)?;
+
+    if history_path.exists() {
+        let content = fs::read_to_string(&history_path)?;
+        let line_count = content.lines().count();
+        if line_count >= MAX_HISTORY_ENTRIES {
+            let kept: Vec<&str> = content
+                .lines()
+                .skip(line_count + 1 - MAX_HISTORY_ENTRIES)
+                .collect();
+            return write_atomic(&history_path, &format!("{}\n{line}\n", kept.join("\n")));
+        }
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&history_path)?;
+    writeln!(file, "{line}")?;
+
+    Ok(())
+}
+
+fn get_history_path() -> anyhow::Result<PathBuf> {
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+
+    Ok(home_dir.join(".shorty").join("history.log"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::add::{add_alias, AddOptions};
+    use crate::utils::test_support::FakeHome;
+
+    #[test]
+    fn add_records_an_entry_that_history_reads_back() {
+        let home = FakeHome::new("history-add");
+
+        add_alias("gs", "git status", &None, &[], &AddOptions::default()).unwrap();
+
+        let entries = read_history(&home.path().join(".shorty").join("history.log")).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].operation, "add");
+        assert_eq!(entries[0].details, "gs -> git status");
+    }
+
+    #[test]
+    fn rotation_keeps_the_newest_entries() {
+        let home = FakeHome::new("history-rotate");
+        let old: Vec<String> = (0..MAX_HISTORY_ENTRIES)
+            .map(|i| format!(r#"{{"timestamp":"t","operation":"op{i}","details":""}}"#))
+            .collect();
+        let path = home.write("history.log", &format!("{}\n", old.join("\n")));
+
+        record_operation("add", "new");
+
+        let entries = read_history(&path).unwrap();
+        assert_eq!(entries.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(entries[0].operation, "op1");
+        assert_eq!(entries.last().unwrap().details, "new");
+    }
+}
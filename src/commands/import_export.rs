@@ -1,27 +1,117 @@
-use crate::utils::get_aliases_path;
+use crate::alias::{self, Alias};
+use crate::commands::backup::backup_before_edit;
+use crate::commands::history::record_operation;
+use crate::utils::{
+    acquire_lock, get_aliases_path, read_aliases_file, resolve_output_path, write_aliases_atomic,
+};
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
+    io::{self, Write},
     path::{Path, PathBuf},
+    process::{Command, Stdio},
 };
 
+const PROGRESS_THRESHOLD: usize = 100;
+
 #[derive(Debug, Serialize, Deserialize)]
-struct AliasData {
-    name: String,
-    command: String,
-    note: Option<String>,
-    tags: Vec<String>,
+pub(crate) struct AliasData {
+    pub(crate) name: String,
+    pub(crate) command: String,
+    #[serde(default)]
+    pub(crate) note: Option<String>,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    #[serde(default)]
+    pub(crate) category: Option<String>,
+    #[serde(default)]
     created_at: Option<String>,
+    #[serde(default)]
     shell_source: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct AliasesFile {
+    aliases: Vec<AliasData>,
+}
+
+#[derive(Debug, Serialize)]
+struct AliasesExport<'a> {
+    version: &'a str,
+    aliases: &'a [AliasData],
+}
+
+impl<'a> AliasesExport<'a> {
+    fn new(aliases: &'a [AliasData]) -> Self {
+        AliasesExport {
+            version: "1.0",
+            aliases,
+        }
+    }
+}
+
+impl From<&AliasData> for Alias {
+    fn from(alias: &AliasData) -> Self {
+        Alias {
+            name: alias.name.clone(),
+            command: alias.command.clone(),
+            note: alias.note.clone(),
+            tags: alias.tags.clone(),
+            category: alias.category.clone(),
+        }
+    }
+}
+
+impl From<Alias> for AliasData {
+    fn from(alias: Alias) -> Self {
+        AliasData {
+            name: alias.name,
+            command: alias.command,
+            note: alias.note,
+            tags: alias.tags,
+            category: alias.category,
+            created_at: None,
+            shell_source: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ImportSummary {
+    added: usize,
+    skipped_conflict: usize,
+    overwritten: usize,
+    renamed: usize,
+    invalid: usize,
+}
+
+impl ImportSummary {
+    fn print(&self) {
+        println!("Import summary:");
+        println!("   • Added: {}", self.added);
+        println!("   • Skipped (conflict): {}", self.skipped_conflict);
+        println!("   • Overwritten: {}", self.overwritten);
+        println!("   • Renamed: {}", self.renamed);
+        println!("   • Invalid: {}", self.invalid);
+    }
+}
+
 #[derive(Debug)]
 pub enum ExportFormat {
     Json,
     Csv,
     Bash,
+    Toml,
+    Yaml,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    Skip,
+    Overwrite,
+    Rename,
 }
 
 #[derive(Debug)]
@@ -30,6 +120,7 @@ pub enum ImportSource {
     Bash,
     Zsh,
     Fish,
+    Live,
 }
 
 impl std::str::FromStr for ExportFormat {
@@ -40,7 +131,28 @@ impl std::str::FromStr for ExportFormat {
             "json" => Ok(ExportFormat::Json),
             "csv" => Ok(ExportFormat::Csv),
             "bash" => Ok(ExportFormat::Bash),
-            _ => anyhow::bail!("Unsupported format: {}. Supported: json, csv, bash", s),
+            "toml" => Ok(ExportFormat::Toml),
+            "yaml" | "yml" => Ok(ExportFormat::Yaml),
+            _ => anyhow::bail!(
+                "Unsupported format: {}. Supported: json, csv, bash, toml, yaml",
+                s
+            ),
+        }
+    }
+}
+
+impl std::str::FromStr for ConflictStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(ConflictStrategy::Skip),
+            "overwrite" => Ok(ConflictStrategy::Overwrite),
+            "rename" => Ok(ConflictStrategy::Rename),
+            _ => anyhow::bail!(
+                "Unsupported conflict strategy: {}. Supported: skip, overwrite, rename",
+                s
+            ),
         }
     }
 }
@@ -53,20 +165,30 @@ impl std::str::FromStr for ImportSource {
             "bash" => Ok(ImportSource::Bash),
             "zsh" => Ok(ImportSource::Zsh),
             "fish" => Ok(ImportSource::Fish),
+            "live" => Ok(ImportSource::Live),
             path => Ok(ImportSource::File(PathBuf::from(path))),
         }
     }
 }
 
-pub fn export_aliases(format: ExportFormat, output_path: Option<&str>) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
+pub fn export_aliases(
+    format: ExportFormat,
+    output_path: Option<&str>,
+    output_dir: Option<&str>,
+    compact: bool,
+    sort: bool,
+) -> anyhow::Result<()> {
+    let aliases_path = get_aliases_path()?;
 
     if !aliases_path.exists() {
         println!("No aliases file found at {}", aliases_path.display());
         return Ok(());
     }
 
-    let aliases = parse_aliases_file(&aliases_path)?;
+    let mut aliases = parse_aliases_file(&aliases_path)?;
+    if sort {
+        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+    }
 
     if aliases.is_empty() {
         println!("No aliases found to export");
@@ -74,9 +196,13 @@ pub fn export_aliases(format: ExportFormat, output_path: Option<&str>) -> anyhow
     }
 
     let content = match format {
-        ExportFormat::Json => export_to_json(&aliases)?,
+        ExportFormat::Json => export_to_json(&aliases, compact, !sort)?,
         ExportFormat::Csv => export_to_csv(&aliases)?,
-        ExportFormat::Bash => export_to_bash(&aliases)?,
+        ExportFormat::Bash => export_to_bash(&aliases, !sort)?,
+        ExportFormat::Toml => toml::to_string_pretty(&AliasesExport::new(&aliases))?,
+        // JSON is valid flow-style YAML, which serde_yaml cannot emit itself.
+        ExportFormat::Yaml if compact => serde_json::to_string(&AliasesExport::new(&aliases))?,
+        ExportFormat::Yaml => serde_yaml::to_string(&AliasesExport::new(&aliases))?,
     };
 
     let output_file = match output_path {
@@ -87,8 +213,13 @@ pub fn export_aliases(format: ExportFormat, output_path: Option<&str>) -> anyhow
                 ExportFormat::Json => "json",
                 ExportFormat::Csv => "csv",
                 ExportFormat::Bash => "sh",
+                ExportFormat::Toml => "toml",
+                ExportFormat::Yaml => "yaml",
             };
-            PathBuf::from(format!("shorty_export_{timestamp}. {extension}"))
+            resolve_output_path(
+                output_dir,
+                &format!("shorty_export_{timestamp}.{extension}"),
+            )?
         }
     };
 
@@ -117,8 +248,36 @@ pub fn import_aliases(
     source: ImportSource,
     format: Option<&str>,
     dry_run: bool,
+    on_conflict: Option<ConflictStrategy>,
+    rename_prefix: Option<&str>,
+    tags: &[String],
 ) -> anyhow::Result<()> {
-    let aliases = match source {
+    run_import(source, format, dry_run, on_conflict, rename_prefix, tags).map(|_| ())
+}
+
+fn run_import(
+    source: ImportSource,
+    format: Option<&str>,
+    dry_run: bool,
+    on_conflict: Option<ConflictStrategy>,
+    rename_prefix: Option<&str>,
+    tags: &[String],
+) -> anyhow::Result<ImportSummary> {
+    if let Some(prefix) = rename_prefix {
+        if prefix.is_empty() || prefix.contains(|c: char| c.is_whitespace() || "='\"".contains(c)) {
+            anyhow::bail!("Invalid rename prefix: '{}'", prefix);
+        }
+    }
+
+    let source_desc = match &source {
+        ImportSource::File(path) => path.display().to_string(),
+        ImportSource::Bash => "bash".to_string(),
+        ImportSource::Zsh => "zsh".to_string(),
+        ImportSource::Fish => "fish".to_string(),
+        ImportSource::Live => "live shell".to_string(),
+    };
+
+    let mut aliases = match source {
         ImportSource::File(path) => {
             println!("Importing from file: {}", path.display());
             import_from_file(&path, format)?
@@ -135,11 +294,26 @@ pub fn import_aliases(
             println!("Importing from Fish configuration...");
             import_from_fish()?
         }
+        ImportSource::Live => import_from_live_shell()?,
     };
 
+    if let Some(prefix) = rename_prefix {
+        for alias in &mut aliases {
+            alias.name = format!("{prefix}{}", alias.name);
+        }
+    }
+
+    for alias in &mut aliases {
+        for tag in tags {
+            if !alias.tags.contains(tag) {
+                alias.tags.push(tag.clone());
+            }
+        }
+    }
+
     if aliases.is_empty() {
         println!("No aliases found to import");
-        return Ok(());
+        return Ok(ImportSummary::default());
     }
 
     println!(
@@ -161,17 +335,40 @@ pub fn import_aliases(
             );
         }
         println!("\nRun without --dry-run to actually import these aliases");
-        return Ok(());
+        return Ok(ImportSummary::default());
     }
 
-    let existing_aliases = parse_aliases_file(&get_aliases_path()).unwrap_or_default();
-    let existing_names: std::collections::HashSet<_> =
-        existing_aliases.iter().map(|a| &a.name).collect();
+    let aliases_path = get_aliases_path()?;
+    let existing_aliases = parse_aliases_file(&aliases_path).unwrap_or_default();
+    let mut taken_names: HashSet<String> =
+        existing_aliases.iter().map(|a| a.name.clone()).collect();
+
+    let total = aliases.len();
+    let show_progress = total >= PROGRESS_THRESHOLD;
+    let mut summary = ImportSummary::default();
+    let mut conflicts = Vec::new();
+    let mut safe_aliases = Vec::new();
+
+    for (index, alias) in aliases.into_iter().enumerate() {
+        if !is_valid_import(&alias) {
+            summary.invalid += 1;
+        } else if taken_names.contains(&alias.name) {
+            conflicts.push(alias);
+        } else {
+            taken_names.insert(alias.name.clone());
+            safe_aliases.push(alias);
+        }
 
-    let conflicts: Vec<_> = aliases
-        .iter()
-        .filter(|a| existing_names.contains(&a.name))
-        .collect();
+        if show_progress && ((index + 1) % PROGRESS_THRESHOLD == 0 || index + 1 == total) {
+            print!("\rProcessing aliases: {}/{total}", index + 1);
+            std::io::stdout().flush()?;
+        }
+    }
+    if show_progress {
+        println!();
+    }
+
+    let mut overwrites: Vec<AliasData> = Vec::new();
 
     if !conflicts.is_empty() {
         println!(
@@ -182,119 +379,117 @@ pub fn import_aliases(
             println!("  • {}", alias.name);
         }
 
-        println!("\nHow do you want to handle conflicts?");
-        println!("  1. Skip conflicting aliases (safe)");
-        println!("  2. Overwrite existing aliases");
-        println!("  3. Rename with suffix (e.g., alias_imported)");
+        let strategy = match on_conflict {
+            Some(strategy) => strategy,
+            None => prompt_conflict_strategy()?,
+        };
 
-        println!(
-            "Skipping {} conflicting aliases for safety",
-            conflicts.len()
-        );
+        match strategy {
+            ConflictStrategy::Skip => {
+                println!("Skipping {} conflicting aliases", conflicts.len());
+                summary.skipped_conflict = conflicts.len();
+            }
+            ConflictStrategy::Overwrite => {
+                for alias in conflicts {
+                    match safe_aliases
+                        .iter_mut()
+                        .chain(overwrites.iter_mut())
+                        .find(|pending| pending.name == alias.name)
+                    {
+                        Some(pending) => *pending = alias,
+                        None => overwrites.push(alias),
+                    }
+                }
+                summary.overwritten = overwrites.len();
+            }
+            ConflictStrategy::Rename => {
+                for mut alias in conflicts {
+                    let new_name = imported_name(&alias.name, &taken_names);
+                    println!("  {} → {new_name}", alias.name);
+                    taken_names.insert(new_name.clone());
+                    alias.name = new_name;
+                    safe_aliases.push(alias);
+                    summary.renamed += 1;
+                }
+            }
+        }
     }
 
-    let safe_aliases: Vec<_> = aliases
-        .into_iter()
-        .filter(|a| !existing_names.contains(&a.name))
-        .collect();
-
-    if safe_aliases.is_empty() {
-        println!("All aliases would conflict with existing ones. Import cancelled for safety.");
-        return Ok(());
+    if safe_aliases.is_empty() && overwrites.is_empty() {
+        println!("No aliases left to import after skipping conflicts and invalid entries.");
+        summary.print();
+        return Ok(summary);
     }
 
-    append_aliases_to_file(&safe_aliases)?;
+    write_imported_aliases(&safe_aliases, &overwrites)?;
+    summary.added = safe_aliases.len() - summary.renamed;
+    let imported = safe_aliases.len() + overwrites.len();
+    record_operation("import", &format!("{imported} aliases from {source_desc}"));
 
-    println!("Successfully imported {} aliases", safe_aliases.len());
-    println!("Aliases added to: {}", get_aliases_path().display());
+    println!("Successfully imported {imported} aliases");
+    println!("Aliases added to: {}", aliases_path.display());
+    summary.print();
 
-    Ok(())
+    Ok(summary)
 }
 
-fn parse_aliases_file(path: &Path) -> anyhow::Result<Vec<AliasData>> {
-    let content = fs::read_to_string(path)?;
-    let mut aliases = Vec::new();
-
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-
-        if let Some(alias) = parse_alias_line(line) {
-            aliases.push(alias);
-        }
-    }
-
-    Ok(aliases)
+fn prompt_conflict_strategy() -> anyhow::Result<ConflictStrategy> {
+    println!("\nHow do you want to handle conflicts?");
+    println!("  1. Skip conflicting aliases (safe)");
+    println!("  2. Overwrite existing aliases");
+    println!("  3. Rename with suffix (e.g., alias_imported)");
+    print!("Choose [1-3] (default 1): ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(match input.trim() {
+        "2" => ConflictStrategy::Overwrite,
+        "3" => ConflictStrategy::Rename,
+        _ => ConflictStrategy::Skip,
+    })
 }
 
-fn parse_alias_line(line: &str) -> Option<AliasData> {
-    if !line.starts_with("alias ") {
-        return None;
-    }
-
-    let eq_pos = line.find('=')?;
-    let name = line[6..eq_pos].trim().to_string();
-    let rest = &line[eq_pos + 1..];
-
-    let mut command = String::new();
-    let mut remaining = "";
-
-    let rest = rest.trim();
-    if let Some(stripped) = rest.strip_prefix('\'') {
-        if let Some(end_quote) = stripped.find('\'') {
-            command = stripped[..end_quote].to_string();
-            remaining = &rest[end_quote + 2..];
-        }
-    } else if let Some(stripped) = rest.strip_prefix('"') {
-        if let Some(end_quote) = stripped.find('"') {
-            command = stripped[..end_quote].to_string();
-            remaining = &rest[end_quote + 2..];
-        }
-    } else if let Some(hash_pos) = rest.find('#') {
-        command = rest[..hash_pos].trim().to_string();
-        remaining = &rest[hash_pos..];
-    } else {
-        command = rest.to_string();
+fn imported_name(name: &str, taken_names: &HashSet<String>) -> String {
+    let base = format!("{name}_imported");
+    if !taken_names.contains(&base) {
+        return base;
     }
+    (2..)
+        .map(|n| format!("{base}_{n}"))
+        .find(|candidate| !taken_names.contains(candidate))
+        .unwrap_or(base)
+}
 
-    let mut note = None;
-    let mut tags = Vec::new();
-
-    if let Some(tags_pos) = remaining.find("#tags:") {
-        let tags_part = &remaining[tags_pos + 6..];
-        tags = tags_part.split(',').map(|s| s.trim().to_string()).collect();
-
-        let note_part = remaining[..tags_pos].trim();
-        if let Some(stripped) = note_part.strip_prefix('#') {
-            let note_text = stripped.trim();
-            if !note_text.is_empty() {
-                note = Some(note_text.to_string());
-            }
-        }
-    } else if remaining.trim().starts_with('#') {
-        let note_text = remaining.trim()[1..].trim();
-        if !note_text.is_empty() {
-            note = Some(note_text.to_string());
-        }
-    }
+fn is_valid_import(alias: &AliasData) -> bool {
+    !alias.name.is_empty()
+        && !alias.command.trim().is_empty()
+        && !alias
+            .name
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '=' | '\'' | '"'))
+}
 
-    Some(AliasData {
-        name,
-        command,
-        note,
-        tags,
-        created_at: Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
-        shell_source: None,
-    })
+fn parse_aliases_file(path: &Path) -> anyhow::Result<Vec<AliasData>> {
+    let content = read_aliases_file(path)?;
+    Ok(alias::parse_content(&content)
+        .into_iter()
+        .map(AliasData::from)
+        .collect())
 }
 
-fn export_to_json(aliases: &[AliasData]) -> anyhow::Result<String> {
+fn export_to_json(
+    aliases: &[AliasData],
+    compact: bool,
+    timestamped: bool,
+) -> anyhow::Result<String> {
     let mut export_data = HashMap::new();
     export_data.insert("version", "1.0");
     let timestamp = Local::now().to_rfc3339();
-    export_data.insert("exported_at", &timestamp);
+    if timestamped {
+        export_data.insert("exported_at", &timestamp);
+    }
     export_data.insert("tool", "shorty");
 
     let json_aliases = serde_json::to_value(aliases)?;
@@ -303,7 +498,11 @@ fn export_to_json(aliases: &[AliasData]) -> anyhow::Result<String> {
     full_export.insert("metadata".to_string(), serde_json::to_value(export_data)?);
     full_export.insert("aliases".to_string(), json_aliases);
 
-    Ok(serde_json::to_string_pretty(&full_export)?)
+    if compact {
+        Ok(serde_json::to_string(&full_export)?)
+    } else {
+        Ok(serde_json::to_string_pretty(&full_export)?)
+    }
 }
 
 fn export_to_csv(aliases: &[AliasData]) -> anyhow::Result<String> {
@@ -329,14 +528,17 @@ fn export_to_csv(aliases: &[AliasData]) -> anyhow::Result<String> {
     Ok(csv)
 }
 
-fn export_to_bash(aliases: &[AliasData]) -> anyhow::Result<String> {
+fn export_to_bash(aliases: &[AliasData], timestamped: bool) -> anyhow::Result<String> {
     let mut bash = String::new();
     bash.push_str("#!/bin/bash\n");
     bash.push_str("# Exported by Shorty alias manager\n");
-    bash.push_str(&format!(
-        "# Generated on: {}\n\n",
-        Local::now().format("%Y-%m-%d %H:%M:%S")
-    ));
+    if timestamped {
+        bash.push_str(&format!(
+            "# Generated on: {}\n",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        ));
+    }
+    bash.push('\n');
 
     for alias in aliases {
         let mut comment_parts = Vec::new();
@@ -353,8 +555,13 @@ fn export_to_bash(aliases: &[AliasData]) -> anyhow::Result<String> {
             bash.push_str(&format!("# {}\n", comment_parts.join(" | ")));
         }
 
-        bash.push_str(&format!("alias {}='{}'\n", alias.name, alias.command));
-        bash.push('\n');
+        let entry = Alias {
+            name: alias.name.clone(),
+            command: alias.command.clone(),
+            ..Alias::default()
+        };
+        bash.push_str(&alias::to_line(&entry));
+        bash.push_str("\n\n");
     }
 
     Ok(bash)
@@ -371,12 +578,20 @@ fn import_from_file(path: &Path, format: Option<&str>) -> anyhow::Result<Vec<Ali
         Some("json") => import_from_json(&content),
         Some("csv") => import_from_csv(&content),
         Some("bash") | Some("sh") => import_from_bash_file(&content),
+        Some("env") => import_from_env(&content),
+        Some("toml") => Ok(toml::from_str::<AliasesFile>(&content)?.aliases),
+        Some("yaml") | Some("yml") => Ok(serde_yaml::from_str::<AliasesFile>(&content)?.aliases),
         None => {
-            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            if path.file_name().is_some_and(|name| name == ".env") {
+                import_from_env(&content)
+            } else if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
                 match ext {
                     "json" => import_from_json(&content),
                     "csv" => import_from_csv(&content),
                     "sh" | "bash" => import_from_bash_file(&content),
+                    "env" => import_from_env(&content),
+                    "toml" => Ok(toml::from_str::<AliasesFile>(&content)?.aliases),
+                    "yaml" | "yml" => Ok(serde_yaml::from_str::<AliasesFile>(&content)?.aliases),
                     _ => import_from_bash_file(&content),
                 }
             } else {
@@ -441,6 +656,7 @@ fn import_from_csv(content: &str) -> anyhow::Result<Vec<AliasData>> {
                 command,
                 note,
                 tags,
+                category: None,
                 created_at: Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
                 shell_source: Some("csv".to_string()),
             });
@@ -451,6 +667,18 @@ fn import_from_csv(content: &str) -> anyhow::Result<Vec<AliasData>> {
 }
 
 fn import_from_bash_file(content: &str) -> anyhow::Result<Vec<AliasData>> {
+    let aliases = alias::parse_content(content)
+        .into_iter()
+        .map(|alias| AliasData {
+            shell_source: Some("bash".to_string()),
+            ..AliasData::from(alias)
+        })
+        .collect();
+
+    Ok(aliases)
+}
+
+fn import_from_env(content: &str) -> anyhow::Result<Vec<AliasData>> {
     let mut aliases = Vec::new();
 
     for line in content.lines() {
@@ -459,11 +687,29 @@ fn import_from_bash_file(content: &str) -> anyhow::Result<Vec<AliasData>> {
             continue;
         }
 
-        if let Some(alias) = parse_alias_line(line) {
-            let mut alias = alias;
-            alias.shell_source = Some("bash".to_string());
-            aliases.push(alias);
-        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        let value = value.trim();
+        let command = if value.starts_with('\'') || value.starts_with('"') {
+            match alias::parse_shell_word(value) {
+                Some((command, _)) => command,
+                None => continue,
+            }
+        } else {
+            value.to_string()
+        };
+
+        aliases.push(AliasData {
+            shell_source: Some("env".to_string()),
+            ..AliasData::from(Alias {
+                name: name.trim().to_string(),
+                command,
+                ..Alias::default()
+            })
+        });
     }
 
     Ok(aliases)
@@ -578,16 +824,71 @@ fn import_from_fish() -> anyhow::Result<Vec<AliasData>> {
     Ok(aliases)
 }
 
+fn import_from_live_shell() -> anyhow::Result<Vec<AliasData>> {
+    let shell = std::env::var("SHELL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string());
+    let shell_name = Path::new(&shell)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| shell.clone());
+
+    println!("Importing from live {shell_name} session...");
+
+    let output = Command::new(&shell)
+        .args(["-ic", "alias"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", shell, e))?;
+
+    if !output.status.success() {
+        anyhow::bail!("'{} -ic alias' exited with {}", shell, output.status);
+    }
+
+    let aliases = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_alias_builtin_line)
+        .map(|(name, command)| AliasData {
+            shell_source: Some(shell_name.clone()),
+            ..AliasData::from(Alias {
+                name,
+                command,
+                ..Alias::default()
+            })
+        })
+        .collect();
+
+    Ok(aliases)
+}
+
+// Handles `alias name='cmd'` (bash), `name='cmd'` or `name=cmd` (zsh), and
+// `alias name 'cmd'` (fish).
+fn parse_alias_builtin_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    let rest = line.strip_prefix("alias ").unwrap_or(line).trim_start();
+
+    let name_end = rest.find(|c: char| c == '=' || c.is_whitespace())?;
+    let (name, value) = (&rest[..name_end], &rest[name_end + 1..]);
+    let name = alias::parse_shell_word(name)?.0;
+    let (command, remaining) = alias::parse_shell_word(value.trim_start())?;
+
+    if name.is_empty() || command.is_empty() || !remaining.trim().is_empty() {
+        return None;
+    }
+
+    Some((name, command))
+}
+
 fn extract_aliases_from_shell_file(path: &Path) -> anyhow::Result<Vec<AliasData>> {
     let content = fs::read_to_string(path)?;
     let mut aliases = Vec::new();
 
     for line in content.lines() {
         let line = line.trim();
-        if line.starts_with("alias ") {
-            if let Some(alias) = parse_alias_line(line) {
-                aliases.push(alias);
-            }
+        if let Some(alias) = alias::parse_line(line) {
+            aliases.push(AliasData::from(alias));
         }
     }
 
@@ -628,50 +929,300 @@ fn parse_fish_abbr(line: &str) -> Option<AliasData> {
         command,
         note: Some("Imported from Fish abbreviation".to_string()),
         tags: vec!["fish".to_string()],
+        category: None,
         created_at: Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
         shell_source: Some("fish".to_string()),
     })
 }
 
-fn append_aliases_to_file(aliases: &[AliasData]) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
-
-    if !aliases_path.exists() {
-        fs::write(&aliases_path, "")?;
+fn replace_alias_definitions(content: &str, replacements: &[AliasData]) -> String {
+    let entries: HashMap<usize, alias::AliasEntry> = alias::parse_entries(content)
+        .into_iter()
+        .map(|entry| (entry.line_number, entry))
+        .collect();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut replaced = HashSet::new();
+    let mut new_lines = Vec::with_capacity(lines.len());
+    let mut i = 0;
+
+    while i < lines.len() {
+        let replacement = entries.get(&(i + 1)).and_then(|entry| {
+            replacements
+                .iter()
+                .find(|alias| alias.name == entry.alias.name)
+                .map(|alias| (entry.line_count, alias))
+        });
+
+        match replacement {
+            Some((line_count, alias)) => {
+                if replaced.insert(alias.name.as_str()) {
+                    new_lines.push(alias::to_line(&Alias::from(alias)));
+                }
+                i += line_count;
+            }
+            None => {
+                new_lines.push(lines[i].to_string());
+                i += 1;
+            }
+        }
     }
 
-    let mut content = fs::read_to_string(&aliases_path)?;
+    let mut new_content = new_lines.join("\n");
+    new_content.push('\n');
+    new_content
+}
 
-    if !content.is_empty() && !content.ends_with('\n') {
-        content.push('\n');
-    }
+fn write_imported_aliases(aliases: &[AliasData], overwrites: &[AliasData]) -> anyhow::Result<()> {
+    let _lock = acquire_lock()?;
+    let aliases_path = get_aliases_path()?;
 
-    content.push_str(&format!(
-        "\n# Imported aliases - {}\n",
-        Local::now().format("%Y-%m-%d %H:%M:%S")
-    ));
+    let existing = if aliases_path.exists() {
+        read_aliases_file(&aliases_path)?
+    } else {
+        String::new()
+    };
 
-    for alias in aliases {
-        let mut alias_line = format!("alias {}='{}'", alias.name, alias.command);
+    let mut content = if overwrites.is_empty() {
+        existing
+    } else {
+        backup_before_edit()?;
+        replace_alias_definitions(&existing, overwrites)
+    };
 
-        let mut comment_parts = Vec::new();
-        if let Some(note) = &alias.note {
-            comment_parts.push(note.clone());
+    if !aliases.is_empty() {
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
         }
 
-        if !alias.tags.is_empty() {
-            comment_parts.push(format!("#tags:{}", alias.tags.join(",")));
+        content.push_str(&format!(
+            "\n# Imported aliases - {}\n",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        ));
+
+        for alias in aliases {
+            content.push_str(&alias::to_line(&Alias::from(alias)));
+            content.push('\n');
         }
+    }
 
-        if !comment_parts.is_empty() {
-            alias_line.push_str(&format!(" # {}", comment_parts.join(" ")));
+    write_aliases_atomic(&content)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+
+    #[test]
+    fn rename_prefix_namespaces_every_imported_alias() {
+        let home = FakeHome::new("import-prefix");
+        let aliases_path = home.write("aliases", "alias gs='git status'\nalias ll='ls -la'\n");
+        let source = home.path().join("team.sh");
+        fs::write(&source, "alias gs='git status -sb'\nalias gp='git push'\n").unwrap();
+
+        import_aliases(
+            ImportSource::File(source),
+            Some("bash"),
+            false,
+            Some(ConflictStrategy::Skip),
+            Some("team_"),
+            &[],
+        )
+        .unwrap();
+
+        let names: Vec<String> = alias::parse_content(&fs::read_to_string(&aliases_path).unwrap())
+            .into_iter()
+            .map(|a| a.name)
+            .collect();
+        assert_eq!(names, ["gs", "ll", "team_gs", "team_gp"]);
+    }
+
+    #[test]
+    fn sorted_exports_do_not_depend_on_file_order() {
+        let home = FakeHome::new("export-sort");
+        let orders = [
+            "alias zz='echo z' #tags:b,a\nalias aa='echo a'\nalias mm='echo m' # middle\n",
+            "alias mm='echo m' # middle\nalias zz='echo z' #tags:b,a\nalias aa='echo a'\n",
+        ];
+
+        for name in ["json", "csv", "toml", "yaml", "bash"] {
+            let exports: Vec<String> = orders
+                .iter()
+                .enumerate()
+                .map(|(i, content)| {
+                    home.write("aliases", content);
+                    let output = home.path().join(format!("export-{i}.{name}"));
+                    export_aliases(name.parse().unwrap(), output.to_str(), None, false, true)
+                        .unwrap();
+                    fs::read_to_string(output).unwrap()
+                })
+                .collect();
+
+            assert_eq!(exports[0], exports[1], "{name}");
+            let position = |command: &str| exports[0].find(command).unwrap();
+            assert!(position("echo a") < position("echo m"), "{name}");
+            assert!(position("echo m") < position("echo z"), "{name}");
         }
+    }
 
-        content.push_str(&alias_line);
-        content.push('\n');
+    #[test]
+    fn summary_counts_each_outcome_of_a_mixed_import() {
+        let home = FakeHome::new("import-summary");
+        let source = home.path().join("mixed.json");
+        fs::write(
+            &source,
+            r#"[
+                {"name": "gs", "command": "git status -sb"},
+                {"name": "gp", "command": "git push"},
+                {"name": "bad name", "command": "echo nope"},
+                {"name": "empty", "command": "  "}
+            ]"#,
+        )
+        .unwrap();
+
+        for (strategy, skipped, renamed) in [
+            (ConflictStrategy::Skip, 1, 0),
+            (ConflictStrategy::Rename, 0, 1),
+        ] {
+            home.write("aliases", "alias gs='git status'\n");
+            let summary = run_import(
+                ImportSource::File(source.clone()),
+                None,
+                false,
+                Some(strategy),
+                None,
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(summary.added, 1, "{strategy:?}");
+            assert_eq!(summary.skipped_conflict, skipped, "{strategy:?}");
+            assert_eq!(summary.renamed, renamed, "{strategy:?}");
+            assert_eq!(summary.overwritten, 0, "{strategy:?}");
+            assert_eq!(summary.invalid, 2, "{strategy:?}");
+        }
     }
 
-    fs::write(&aliases_path, content)?;
+    #[test]
+    fn compact_exports_are_single_line_and_still_parse() {
+        let home = FakeHome::new("export-compact");
+        home.write(
+            "aliases",
+            "alias gs='git status' # show status #tags:git,vcs\nalias ll='ls -la'\n",
+        );
+
+        for name in ["json", "yaml"] {
+            let export = |compact: bool| {
+                let output = home.path().join(format!("export-{compact}.{name}"));
+                export_aliases(name.parse().unwrap(), output.to_str(), None, compact, true)
+                    .unwrap();
+                fs::read_to_string(output).unwrap()
+            };
+            let (pretty, compact) = (export(false), export(true));
 
-    Ok(())
+            assert!(!compact.contains('\n'), "{name}: {compact}");
+            assert!(
+                !compact.contains(": ") && !compact.contains(", "),
+                "{name}: {compact}"
+            );
+
+            let parse = |content: &str| -> Vec<(String, String, Vec<String>)> {
+                let file: AliasesFile = if name == "json" {
+                    serde_json::from_str(content).unwrap()
+                } else {
+                    serde_yaml::from_str(content).unwrap()
+                };
+                file.aliases
+                    .into_iter()
+                    .map(|a| (a.name, a.command, a.tags))
+                    .collect()
+            };
+            assert_eq!(parse(&compact), parse(&pretty), "{name}");
+            assert_eq!(parse(&compact)[0].2, ["git", "vcs"], "{name}");
+        }
+    }
+
+    #[test]
+    fn env_files_import_each_assignment_as_an_alias() {
+        let home = FakeHome::new("import-env");
+        let aliases_path = home.write("aliases", "");
+        let source = home.path().join(".env");
+        fs::write(
+            &source,
+            "# deploy helpers\n\
+             \n\
+             build=cargo build --release\n\
+             export serve='python3 -m http.server 8000'\n\
+             greet=\"echo hello world\"\n\
+             not an assignment\n",
+        )
+        .unwrap();
+
+        import_aliases(
+            ImportSource::File(source),
+            None,
+            false,
+            Some(ConflictStrategy::Skip),
+            None,
+            &[],
+        )
+        .unwrap();
+
+        let aliases: Vec<(String, String)> =
+            alias::parse_content(&fs::read_to_string(&aliases_path).unwrap())
+                .into_iter()
+                .map(|a| (a.name, a.command))
+                .collect();
+        assert_eq!(
+            aliases,
+            [
+                ("build".to_string(), "cargo build --release".to_string()),
+                (
+                    "serve".to_string(),
+                    "python3 -m http.server 8000".to_string()
+                ),
+                ("greet".to_string(), "echo hello world".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_tags_are_added_to_every_imported_alias() {
+        let home = FakeHome::new("import-tags");
+        let aliases_path = home.write("aliases", "alias ll='ls -la'\n");
+        let source = home.path().join("team.sh");
+        fs::write(
+            &source,
+            "alias gs='git status' #tags:git\nalias gp='git push' #tags:team\n",
+        )
+        .unwrap();
+
+        import_aliases(
+            ImportSource::File(source),
+            Some("bash"),
+            false,
+            Some(ConflictStrategy::Skip),
+            None,
+            &["team".to_string(), "shared".to_string()],
+        )
+        .unwrap();
+
+        let tags: Vec<(String, Vec<String>)> =
+            alias::parse_content(&fs::read_to_string(&aliases_path).unwrap())
+                .into_iter()
+                .map(|a| (a.name, a.tags))
+                .collect();
+        let strings =
+            |tags: &[&str]| -> Vec<String> { tags.iter().map(|t| t.to_string()).collect() };
+        assert_eq!(
+            tags,
+            [
+                ("ll".to_string(), Vec::new()),
+                ("gs".to_string(), strings(&["git", "team", "shared"])),
+                ("gp".to_string(), strings(&["team", "shared"])),
+            ]
+        );
+    }
 }
@@ -7,18 +7,27 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Wrap,
+    },
     Frame, Terminal,
 };
-use std::{
-    fs,
-    io::{self, Stdout},
-};
+use std::collections::HashMap;
+use std::io::{self, Stdout};
+
+use crate::alias::{parse_entries, to_line, AliasEntry};
+use crate::commands::backup::backup_before_edit;
+use crate::commands::config::{Config, SearchConfig};
+use crate::commands::search::{fuzzy_matcher, fuzzy_score, search_fields};
+use crate::commands::sync::copy_to_clipboard;
+use crate::commands::validate::line_warning;
+use crate::utils::{acquire_lock, get_aliases_path, read_aliases_file, write_aliases_atomic};
 
-use crate::utils::get_aliases_path;
+const UNDO_LIMIT: usize = 10;
 
 #[derive(Debug, Clone)]
 struct Alias {
@@ -26,9 +35,31 @@ struct Alias {
     command: String,
     note: Option<String>,
     tags: Vec<String>,
+    category: Option<String>,
     line_number: usize,
 }
 
+impl Alias {
+    fn search_fields(&self, field: Option<&str>, config: &SearchConfig) -> Vec<&str> {
+        search_fields(
+            &self.name,
+            &self.command,
+            self.note.as_deref(),
+            &self.tags,
+            field,
+            config,
+        )
+    }
+}
+
+// What the file held before and right after a browser edit, so undo can tell
+// whether something else has written to it since.
+#[derive(Debug)]
+struct UndoEntry {
+    before: String,
+    after: String,
+}
+
 #[derive(Debug, PartialEq)]
 enum Screen {
     MainMenu,
@@ -37,15 +68,50 @@ enum Screen {
     EditAlias,
     AddAlias,
     ConfirmDelete,
-    #[allow(dead_code)]
     Help,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiscardAction {
+    LeaveEdit,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MenuItem {
+    BrowseAliases,
+    Settings,
+    Help,
+    Exit,
+}
+
+impl MenuItem {
+    fn label(&self) -> &'static str {
+        match self {
+            MenuItem::BrowseAliases => "Browse Aliases",
+            MenuItem::Settings => "Settings",
+            MenuItem::Help => "Help",
+            MenuItem::Exit => "Exit",
+        }
+    }
+}
+
+const MAIN_MENU: &[MenuItem] = &[
+    MenuItem::BrowseAliases,
+    MenuItem::Settings,
+    MenuItem::Help,
+    MenuItem::Exit,
+];
+
 #[derive(Debug)]
 struct App {
     screen: Screen,
     main_menu_state: ListState,
     alias_list_state: ListState,
+    alias_page_size: usize,
+    settings_state: ListState,
+    settings_edit: Option<String>,
+    config: Config,
     aliases: Vec<Alias>,
     filtered_aliases: Vec<usize>,
     search_input: String,
@@ -54,20 +120,27 @@ struct App {
     edit_note: String,
     edit_tags: String,
     edit_index: Option<usize>,
+    edit_warning: Option<String>,
+    dirty: bool,
+    confirm_discard: Option<DiscardAction>,
+    undo_stack: Vec<UndoEntry>,
     delete_index: Option<usize>,
     current_edit_field: usize,
     status_message: Option<String>,
     should_quit: bool,
     show_help: bool,
     search_focused: bool,
+    search_config: SearchConfig,
 }
 
 impl App {
     fn new() -> anyhow::Result<Self> {
+        let config = Config::load_strict().unwrap_or_default();
         let mut app = Self {
             screen: Screen::MainMenu,
             main_menu_state: ListState::default(),
             alias_list_state: ListState::default(),
+            alias_page_size: 10,
             aliases: Vec::new(),
             filtered_aliases: Vec::new(),
             search_input: String::new(),
@@ -76,12 +149,20 @@ impl App {
             edit_note: String::new(),
             edit_tags: String::new(),
             edit_index: None,
+            edit_warning: None,
+            dirty: false,
+            confirm_discard: None,
+            undo_stack: Vec::new(),
             delete_index: None,
             current_edit_field: 0,
             status_message: None,
             should_quit: false,
             show_help: false,
             search_focused: false,
+            search_config: config.search.clone(),
+            settings_state: ListState::default(),
+            settings_edit: None,
+            config,
         };
 
         app.main_menu_state.select(Some(0));
@@ -92,7 +173,7 @@ impl App {
     }
 
     fn load_aliases(&mut self) -> anyhow::Result<()> {
-        let aliases_path = get_aliases_path();
+        let aliases_path = get_aliases_path()?;
 
         if !aliases_path.exists() {
             self.status_message =
@@ -100,19 +181,19 @@ impl App {
             return Ok(());
         }
 
-        let content = fs::read_to_string(&aliases_path)?;
-        self.aliases.clear();
+        let content = read_aliases_file(&aliases_path)?;
 
-        for (line_num, line) in content.lines().enumerate() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
-
-            if let Some(alias) = parse_alias_line(line, line_num + 1) {
-                self.aliases.push(alias);
-            }
-        }
+        self.aliases = parse_entries(&content)
+            .into_iter()
+            .map(|entry| Alias {
+                name: entry.alias.name,
+                command: entry.alias.command,
+                note: entry.alias.note,
+                tags: entry.alias.tags,
+                category: entry.alias.category,
+                line_number: entry.line_number,
+            })
+            .collect();
 
         Ok(())
     }
@@ -125,43 +206,41 @@ impl App {
         if self.search_input.is_empty() {
             self.reset_filter();
         } else {
-            let query = self.search_input.to_lowercase();
-
-            // Check for field-specific search
-            if let Some((field, search_term)) = parse_search_query(&query) {
-                self.filtered_aliases = self
+            let (field, term) = match parse_search_query(&self.search_input) {
+                Some((field, term)) => (Some(field), term),
+                None => (None, self.search_input.clone()),
+            };
+            let config = &self.search_config;
+            if let Some(matcher) = fuzzy_matcher(config) {
+                let mut scored: Vec<(i64, usize)> = self
                     .aliases
                     .iter()
                     .enumerate()
-                    .filter(|(_, alias)| match field {
-                        "name" => alias.name.to_lowercase().contains(&search_term),
-                        "cmd" | "command" => alias.command.to_lowercase().contains(&search_term),
-                        "note" => alias
-                            .note
-                            .as_ref()
-                            .is_some_and(|n| n.to_lowercase().contains(&search_term)),
-                        "tag" | "tags" => alias
-                            .tags
-                            .iter()
-                            .any(|t| t.to_lowercase().contains(&search_term)),
-                        _ => false,
+                    .filter_map(|(i, alias)| {
+                        fuzzy_score(&matcher, &term, alias.search_fields(field, config))
+                            .map(|score| (score, i))
                     })
-                    .map(|(i, _)| i)
                     .collect();
+                scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+                self.filtered_aliases = scored.into_iter().map(|(_, i)| i).collect();
             } else {
-                // Regular search across all fields
+                let fold = |text: &str| {
+                    if config.case_sensitive {
+                        text.to_string()
+                    } else {
+                        text.to_lowercase()
+                    }
+                };
+                let term = fold(&term);
                 self.filtered_aliases = self
                     .aliases
                     .iter()
                     .enumerate()
                     .filter(|(_, alias)| {
-                        alias.name.to_lowercase().contains(&query)
-                            || alias.command.to_lowercase().contains(&query)
-                            || alias
-                                .note
-                                .as_ref()
-                                .is_some_and(|n| n.to_lowercase().contains(&query))
-                            || alias.tags.iter().any(|t| t.to_lowercase().contains(&query))
+                        alias
+                            .search_fields(field, config)
+                            .into_iter()
+                            .any(|text| fold(text).contains(&term))
                     })
                     .map(|(i, _)| i)
                     .collect();
@@ -176,33 +255,54 @@ impl App {
     }
 
     fn handle_main_menu_input(&mut self, key: KeyCode) -> anyhow::Result<()> {
+        let last = MAIN_MENU.len() - 1;
         match key {
             KeyCode::Up | KeyCode::Char('k') => {
                 let current = self.main_menu_state.selected().unwrap_or(0);
-                let new_index = if current > 0 { current - 1 } else { 2 };
+                let new_index = if current > 0 { current - 1 } else { last };
                 self.main_menu_state.select(Some(new_index));
             }
             KeyCode::Down | KeyCode::Char('j') => {
                 let current = self.main_menu_state.selected().unwrap_or(0);
-                let new_index = if current < 2 { current + 1 } else { 0 };
+                let new_index = if current < last { current + 1 } else { 0 };
                 self.main_menu_state.select(Some(new_index));
             }
-            KeyCode::Enter | KeyCode::Char(' ') => match self.main_menu_state.selected() {
-                Some(0) => {
-                    self.screen = Screen::AliasBrowser;
-                    if !self.filtered_aliases.is_empty() {
-                        self.alias_list_state.select(Some(0));
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                match self
+                    .main_menu_state
+                    .selected()
+                    .and_then(|i| MAIN_MENU.get(i))
+                {
+                    Some(MenuItem::BrowseAliases) => {
+                        self.screen = Screen::AliasBrowser;
+                        if !self.filtered_aliases.is_empty() {
+                            self.alias_list_state.select(Some(0));
+                        }
+                        self.search_focused = false;
                     }
-                    self.search_focused = false;
-                }
-                Some(1) => {
-                    self.screen = Screen::Settings;
-                }
-                Some(2) => {
-                    self.should_quit = true;
+                    Some(MenuItem::Settings) => {
+                        self.config = match Config::load_strict() {
+                            Ok(config) => config,
+                            Err(e) => {
+                                self.status_message = Some(format!(
+                                    "config.toml could not be read ({e}); showing defaults, changes will not be saved"
+                                ));
+                                Config::default()
+                            }
+                        };
+                        self.settings_edit = None;
+                        self.settings_state.select(Some(0));
+                        self.screen = Screen::Settings;
+                    }
+                    Some(MenuItem::Help) => {
+                        self.screen = Screen::Help;
+                    }
+                    Some(MenuItem::Exit) => {
+                        self.should_quit = true;
+                    }
+                    None => {}
                 }
-                _ => {}
-            },
+            }
             _ => {}
         }
         Ok(())
@@ -231,27 +331,41 @@ impl App {
             }
         } else {
             match key {
-                KeyCode::Up | KeyCode::Char('k') => {
-                    if !self.filtered_aliases.is_empty() {
-                        let current = self.alias_list_state.selected().unwrap_or(0);
-                        let new_index = if current > 0 {
-                            current - 1
-                        } else {
-                            self.filtered_aliases.len() - 1
-                        };
-                        self.alias_list_state.select(Some(new_index));
-                    }
+                KeyCode::Up | KeyCode::Char('k') if !self.filtered_aliases.is_empty() => {
+                    let current = self.alias_list_state.selected().unwrap_or(0);
+                    let new_index = if current > 0 {
+                        current - 1
+                    } else {
+                        self.filtered_aliases.len() - 1
+                    };
+                    self.alias_list_state.select(Some(new_index));
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if !self.filtered_aliases.is_empty() {
-                        let current = self.alias_list_state.selected().unwrap_or(0);
-                        let new_index = if current < self.filtered_aliases.len() - 1 {
-                            current + 1
-                        } else {
-                            0
-                        };
-                        self.alias_list_state.select(Some(new_index));
-                    }
+                KeyCode::Down | KeyCode::Char('j') if !self.filtered_aliases.is_empty() => {
+                    let current = self.alias_list_state.selected().unwrap_or(0);
+                    let new_index = if current < self.filtered_aliases.len() - 1 {
+                        current + 1
+                    } else {
+                        0
+                    };
+                    self.alias_list_state.select(Some(new_index));
+                }
+                KeyCode::PageUp if !self.filtered_aliases.is_empty() => {
+                    let current = self.alias_list_state.selected().unwrap_or(0);
+                    self.alias_list_state
+                        .select(Some(current.saturating_sub(self.alias_page_size)));
+                }
+                KeyCode::PageDown if !self.filtered_aliases.is_empty() => {
+                    let current = self.alias_list_state.selected().unwrap_or(0);
+                    let last = self.filtered_aliases.len() - 1;
+                    self.alias_list_state
+                        .select(Some((current + self.alias_page_size).min(last)));
+                }
+                KeyCode::Home if !self.filtered_aliases.is_empty() => {
+                    self.alias_list_state.select(Some(0));
+                }
+                KeyCode::End if !self.filtered_aliases.is_empty() => {
+                    self.alias_list_state
+                        .select(Some(self.filtered_aliases.len() - 1));
                 }
                 KeyCode::Char('/') | KeyCode::F(3) => {
                     self.search_focused = true;
@@ -294,6 +408,12 @@ impl App {
                     self.load_aliases()?;
                     self.reset_filter();
                 }
+                KeyCode::Char('u') => {
+                    self.undo()?;
+                }
+                KeyCode::Char(c @ ('y' | 'Y')) => {
+                    self.copy_selected(c == 'Y');
+                }
                 KeyCode::Esc => {
                     self.screen = Screen::MainMenu;
                 }
@@ -304,6 +424,10 @@ impl App {
     }
 
     fn handle_edit_alias_input(&mut self, key: KeyCode) -> anyhow::Result<()> {
+        if matches!(key, KeyCode::Char(_) | KeyCode::Backspace) {
+            self.edit_warning = None;
+            self.dirty = true;
+        }
         match key {
             KeyCode::Tab => {
                 self.current_edit_field = (self.current_edit_field + 1) % 4;
@@ -321,16 +445,31 @@ impl App {
                     return Ok(());
                 }
 
+                if self.edit_warning.is_none() {
+                    self.edit_warning = self.edited_alias_warning();
+                    if self.edit_warning.is_some() {
+                        return Ok(());
+                    }
+                }
+                self.edit_warning = None;
+
                 if let Some(idx) = self.edit_index {
                     self.save_edit_alias(idx)?;
                 } else {
                     self.save_new_alias()?;
                 }
 
+                self.dirty = false;
                 self.screen = Screen::AliasBrowser;
             }
             KeyCode::Esc => {
-                self.screen = Screen::AliasBrowser;
+                self.edit_warning = None;
+                if self.has_unsaved_edits() {
+                    self.confirm_discard = Some(DiscardAction::LeaveEdit);
+                } else {
+                    self.dirty = false;
+                    self.screen = Screen::AliasBrowser;
+                }
             }
             KeyCode::Backspace => match self.current_edit_field {
                 0 => {
@@ -359,6 +498,117 @@ impl App {
         Ok(())
     }
 
+    fn selected_setting(&self) -> Option<String> {
+        let keys = self.config.get_all_keys();
+        let (key, _) = keys.get(self.settings_state.selected()?)?;
+        Some(key.clone())
+    }
+
+    fn handle_settings_input(&mut self, key: KeyCode) -> anyhow::Result<()> {
+        if let Some(buffer) = self.settings_edit.as_mut() {
+            match key {
+                KeyCode::Esc => self.settings_edit = None,
+                KeyCode::Backspace => {
+                    buffer.pop();
+                }
+                KeyCode::Char(c) => buffer.push(c),
+                KeyCode::Enter => {
+                    let value = buffer.trim().to_string();
+                    if let Some(setting) = self.selected_setting() {
+                        self.apply_setting(&setting, &value);
+                    }
+                    self.settings_edit = None;
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        let count = self.config.get_all_keys().len();
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                let current = self.settings_state.selected().unwrap_or(0);
+                let new_index = if current > 0 { current - 1 } else { count - 1 };
+                self.settings_state.select(Some(new_index));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let current = self.settings_state.selected().unwrap_or(0);
+                let new_index = if current < count - 1 { current + 1 } else { 0 };
+                self.settings_state.select(Some(new_index));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let Some(setting) = self.selected_setting() else {
+                    return Ok(());
+                };
+                match self.config.get_value(&setting).as_deref() {
+                    Some("true") => self.apply_setting(&setting, "false"),
+                    Some("false") => self.apply_setting(&setting, "true"),
+                    value => {
+                        self.settings_edit = Some(value.unwrap_or_default().to_string());
+                        self.status_message = None;
+                    }
+                }
+            }
+            KeyCode::Esc => {
+                self.screen = Screen::MainMenu;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn apply_setting(&mut self, key: &str, value: &str) {
+        // Never save defaults over a config.toml that failed to parse.
+        let mut config = match Config::load_strict() {
+            Ok(config) => config,
+            Err(e) => {
+                self.status_message = Some(format!(
+                    "Not saved: config.toml could not be read ({e}). Run 'shorty config validate'"
+                ));
+                return;
+            }
+        };
+        let result = config.set_value(key, value).and_then(|_| config.save());
+        self.status_message = Some(match result {
+            Ok(()) => {
+                self.search_config = config.search.clone();
+                self.config = config;
+                format!("Saved {key} = {value}")
+            }
+            Err(e) => format!("Error: {e}"),
+        });
+    }
+
+    fn has_unsaved_edits(&self) -> bool {
+        matches!(self.screen, Screen::EditAlias | Screen::AddAlias)
+            && self.dirty
+            && [
+                &self.edit_name,
+                &self.edit_command,
+                &self.edit_note,
+                &self.edit_tags,
+            ]
+            .iter()
+            .any(|field| !field.trim().is_empty())
+    }
+
+    fn handle_confirm_discard_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                match self.confirm_discard.take() {
+                    Some(DiscardAction::LeaveEdit) => self.screen = Screen::AliasBrowser,
+                    Some(DiscardAction::Quit) => self.should_quit = true,
+                    None => {}
+                }
+                self.dirty = false;
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                self.confirm_discard = None;
+            }
+            _ => {}
+        }
+    }
+
     fn handle_confirm_delete_input(&mut self, key: KeyCode) -> anyhow::Result<()> {
         match key {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
@@ -378,101 +628,198 @@ impl App {
     }
 
     fn save_edit_alias(&mut self, index: usize) -> anyhow::Result<()> {
-        let aliases_path = get_aliases_path();
-        let content = std::fs::read_to_string(&aliases_path)?;
+        let _lock = acquire_lock()?;
+        let aliases_path = get_aliases_path()?;
+        let content = read_aliases_file(&aliases_path)?;
+        let Some(entry) = self.find_on_disk(&content, index)? else {
+            return Ok(());
+        };
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
-        let alias = &self.aliases[index];
-        let line_idx = alias.line_number - 1;
+        let new_line = self.edited_alias_line(entry.alias.category.clone());
+        let start = entry.line_number - 1;
+        lines.splice(start..start + entry.line_count, [new_line]);
+        let mut new_content = lines.join("\n");
+        new_content.push('\n');
 
-        let tags_part = if self.edit_tags.trim().is_empty() {
-            String::new()
-        } else {
-            format!(" #tags:{}", self.edit_tags.trim())
-        };
+        backup_before_edit()?;
+        write_aliases_atomic(&new_content)?;
+        self.push_undo(content, new_content);
+        self.load_aliases()?;
+        self.reset_filter();
+        self.status_message = Some("Alias updated successfully".to_string());
 
-        let note_part = if self.edit_note.trim().is_empty() {
-            String::new()
+        Ok(())
+    }
+
+    // The file may have changed since the browser loaded it, so look the alias
+    // up by name in the fresh content rather than trusting the cached line.
+    fn find_on_disk(&mut self, content: &str, index: usize) -> anyhow::Result<Option<AliasEntry>> {
+        let name = self.aliases[index].name.clone();
+        let entry = parse_entries(content)
+            .into_iter()
+            .find(|entry| entry.alias.name == name);
+        if entry.is_none() {
+            self.load_aliases()?;
+            self.reset_filter();
+            self.status_message = Some(format!(
+                "Alias '{name}' was removed or renamed outside the browser; list reloaded"
+            ));
+        }
+        Ok(entry)
+    }
+
+    fn edited_alias_line(&self, category: Option<String>) -> String {
+        let note = self.edit_note.trim();
+        to_line(&crate::alias::Alias {
+            name: self.edit_name.trim().to_string(),
+            command: self.edit_command.trim().to_string(),
+            note: (!note.is_empty()).then(|| note.to_string()),
+            tags: self
+                .edit_tags
+                .split(',')
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            category,
+        })
+    }
+
+    fn edited_alias_warning(&self) -> Option<String> {
+        let name = self.edit_name.trim();
+        if name.contains(|c: char| c.is_whitespace() || "='\"".contains(c)) {
+            return Some(format!("Invalid alias name: '{name}'"));
+        }
+
+        let line = self.edited_alias_line(None);
+        if line.contains('\n') {
+            return None;
+        }
+        let others: HashMap<String, usize> = self
+            .aliases
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != self.edit_index)
+            .map(|(_, alias)| (alias.name.clone(), alias.line_number))
+            .collect();
+        line_warning(&line, &others)
+    }
+
+    fn save_new_alias(&mut self) -> anyhow::Result<()> {
+        let _lock = acquire_lock()?;
+        let aliases_path = get_aliases_path()?;
+
+        let new_line = self.edited_alias_line(None);
+
+        let content = if aliases_path.exists() {
+            read_aliases_file(&aliases_path)?
         } else {
-            format!(" # {}", self.edit_note.trim())
+            String::new()
         };
 
-        let new_line = format!(
-            "alias {}='{}'{}{}",
-            self.edit_name.trim(),
-            self.edit_command.trim(),
-            note_part,
-            tags_part
-        );
-
-        if line_idx < lines.len() {
-            lines[line_idx] = new_line;
+        let mut new_content = content.clone();
+        if !new_content.is_empty() && !new_content.ends_with('\n') {
+            new_content.push('\n');
         }
+        new_content.push_str(&new_line);
+        new_content.push('\n');
 
-        std::fs::write(&aliases_path, lines.join("\n"))?;
+        write_aliases_atomic(&new_content)?;
+        self.push_undo(content, new_content);
         self.load_aliases()?;
         self.reset_filter();
-        self.status_message = Some("Alias updated successfully".to_string());
+        self.status_message = Some("Alias added successfully".to_string());
 
         Ok(())
     }
 
-    fn save_new_alias(&mut self) -> anyhow::Result<()> {
-        let aliases_path = get_aliases_path();
-
-        let tags_part = if self.edit_tags.trim().is_empty() {
-            String::new()
-        } else {
-            format!(" #tags:{}", self.edit_tags.trim())
+    fn copy_selected(&mut self, full_line: bool) {
+        let Some(&alias_idx) = self
+            .alias_list_state
+            .selected()
+            .and_then(|selected| self.filtered_aliases.get(selected))
+        else {
+            return;
         };
-
-        let note_part = if self.edit_note.trim().is_empty() {
-            String::new()
+        let alias = &self.aliases[alias_idx];
+        let text = if full_line {
+            to_line(&crate::alias::Alias {
+                name: alias.name.clone(),
+                command: alias.command.clone(),
+                note: alias.note.clone(),
+                tags: alias.tags.clone(),
+                category: alias.category.clone(),
+            })
         } else {
-            format!(" # {}", self.edit_note.trim())
+            alias.command.clone()
         };
 
-        let new_line = format!(
-            "alias {}='{}'{}{}",
-            self.edit_name.trim(),
-            self.edit_command.trim(),
-            note_part,
-            tags_part
-        );
+        self.status_message = Some(match copy_to_clipboard(&text) {
+            Ok(()) if full_line => format!("Copied alias line for '{}' to clipboard", alias.name),
+            Ok(()) => format!("Copied command of '{}' to clipboard", alias.name),
+            Err(e) => format!("Error: {e}"),
+        });
+    }
+
+    fn push_undo(&mut self, before: String, after: String) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(UndoEntry { before, after });
+    }
+
+    fn undo(&mut self) -> anyhow::Result<()> {
+        let Some(entry) = self.undo_stack.pop() else {
+            self.status_message = Some("Nothing to undo".to_string());
+            return Ok(());
+        };
 
-        let mut content = if aliases_path.exists() {
-            std::fs::read_to_string(&aliases_path)?
+        let _lock = acquire_lock()?;
+        let aliases_path = get_aliases_path()?;
+        let current = if aliases_path.exists() {
+            read_aliases_file(&aliases_path)?
         } else {
             String::new()
         };
-
-        if !content.is_empty() && !content.ends_with('\n') {
-            content.push('\n');
+        if current != entry.after {
+            self.undo_stack.clear();
+            self.load_aliases()?;
+            self.reset_filter();
+            self.status_message = Some(
+                "Cannot undo: the aliases file was changed outside the browser; list reloaded"
+                    .to_string(),
+            );
+            return Ok(());
         }
-        content.push_str(&new_line);
-        content.push('\n');
 
-        std::fs::write(&aliases_path, content)?;
+        write_aliases_atomic(&entry.before)?;
         self.load_aliases()?;
         self.reset_filter();
-        self.status_message = Some("Alias added successfully".to_string());
+        self.status_message = Some(format!(
+            "Undid last change ({} more available)",
+            self.undo_stack.len()
+        ));
 
         Ok(())
     }
 
     fn delete_alias(&mut self, index: usize) -> anyhow::Result<()> {
-        let aliases_path = get_aliases_path();
-        let content = std::fs::read_to_string(&aliases_path)?;
+        let _lock = acquire_lock()?;
+        let aliases_path = get_aliases_path()?;
+        let content = read_aliases_file(&aliases_path)?;
+        let Some(entry) = self.find_on_disk(&content, index)? else {
+            return Ok(());
+        };
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
-        let alias = &self.aliases[index];
-        let line_idx = alias.line_number - 1;
-
-        if line_idx < lines.len() {
-            lines.remove(line_idx);
-        }
+        let start = entry.line_number - 1;
+        lines.drain(start..start + entry.line_count);
+        let mut new_content = lines.join("\n");
+        new_content.push('\n');
 
-        std::fs::write(&aliases_path, lines.join("\n"))?;
+        backup_before_edit()?;
+        write_aliases_atomic(&new_content)?;
+        self.push_undo(content, new_content);
         self.load_aliases()?;
         self.reset_filter();
         self.status_message = Some("Alias deleted successfully".to_string());
@@ -517,6 +864,11 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) ->
 
         if let Event::Key(key) = event::read()? {
             if key.kind == KeyEventKind::Press {
+                if app.confirm_discard.is_some() {
+                    app.handle_confirm_discard_input(key.code);
+                    continue;
+                }
+
                 // Handle global Ctrl shortcuts
                 if key.modifiers.contains(KeyModifiers::CONTROL) {
                     match key.code {
@@ -539,6 +891,12 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) ->
                             app.load_aliases()?;
                             app.reset_filter();
                         }
+                        KeyCode::Char('z') if app.screen == Screen::AliasBrowser => {
+                            app.undo()?;
+                        }
+                        KeyCode::Char('q') if app.has_unsaved_edits() => {
+                            app.confirm_discard = Some(DiscardAction::Quit);
+                        }
                         KeyCode::Char('q') => {
                             app.should_quit = true;
                         }
@@ -559,11 +917,7 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) ->
                                 app.handle_edit_alias_input(key.code)?
                             }
                             Screen::ConfirmDelete => app.handle_confirm_delete_input(key.code)?,
-                            Screen::Settings => {
-                                if key.code == KeyCode::Esc {
-                                    app.screen = Screen::MainMenu;
-                                }
-                            }
+                            Screen::Settings => app.handle_settings_input(key.code)?,
                             Screen::Help => {
                                 app.show_help = false;
                                 app.screen = Screen::MainMenu;
@@ -598,17 +952,20 @@ fn ui(f: &mut Frame, app: &mut App) {
 
     render_status_bar(f, chunks[1], app);
 
+    if let Some(action) = app.confirm_discard {
+        render_discard_confirm(f, chunks[0], action);
+    }
+
     if app.show_help {
         render_help_popup(f, app);
     }
 }
 
 fn render_main_menu(f: &mut Frame, area: Rect, app: &mut App) {
-    let menu_items = vec![
-        ListItem::new("Browse Aliases"),
-        ListItem::new("Settings"),
-        ListItem::new("Exit"),
-    ];
+    let menu_items: Vec<ListItem> = MAIN_MENU
+        .iter()
+        .map(|item| ListItem::new(item.label()))
+        .collect();
 
     let list = List::new(menu_items)
         .block(
@@ -689,10 +1046,16 @@ fn render_alias_browser(f: &mut Frame, area: Rect, app: &mut App) {
         })
         .collect();
 
+    let total = app.filtered_aliases.len();
+    let title = match app.alias_list_state.selected().filter(|&i| i < total) {
+        Some(selected) => format!(" Aliases ({} of {total}) ", selected + 1),
+        None => format!(" Aliases ({total}) "),
+    };
+
     let list = List::new(items)
         .block(
             Block::default()
-                .title(format!(" Aliases ({}) ", app.filtered_aliases.len()))
+                .title(title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Green)),
         )
@@ -705,6 +1068,20 @@ fn render_alias_browser(f: &mut Frame, area: Rect, app: &mut App) {
 
     f.render_stateful_widget(list, chunks[0], &mut app.alias_list_state);
 
+    app.alias_page_size = usize::from(chunks[0].height.saturating_sub(2)).max(1);
+    if total > app.alias_page_size {
+        let mut scrollbar_state =
+            ScrollbarState::new(total).position(app.alias_list_state.selected().unwrap_or(0));
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            chunks[0].inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+
     render_alias_details(f, chunks[1], app);
 }
 
@@ -848,15 +1225,63 @@ fn render_delete_confirm(f: &mut Frame, area: Rect, app: &App) {
     }
 }
 
-fn render_settings_screen(f: &mut Frame, area: Rect, _app: &App) {
-    let paragraph = Paragraph::new("Settings screen - Coming soon!\n\nPress ESC to go back").block(
-        Block::default()
-            .title(" Settings ")
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan)),
-    );
+fn render_discard_confirm(f: &mut Frame, area: Rect, action: DiscardAction) {
+    let confirm_area = centered_rect(60, 30, area);
+    f.render_widget(Clear, confirm_area);
 
-    f.render_widget(paragraph, area);
+    let question = match action {
+        DiscardAction::LeaveEdit => "Discard your unsaved changes?",
+        DiscardAction::Quit => "Quit and discard your unsaved changes?",
+    };
+    let text = format!("{question}\n\nPress 'y' to discard, 'n' or ESC to keep editing");
+
+    let popup = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Unsaved Changes ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red)),
+        )
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(popup, confirm_area);
+}
+
+fn render_settings_screen(f: &mut Frame, area: Rect, app: &mut App) {
+    let items: Vec<ListItem> = app
+        .config
+        .get_all_keys()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (key, description))| {
+            let value = match &app.settings_edit {
+                Some(buffer) if app.settings_state.selected() == Some(i) => format!("{buffer}_"),
+                _ => app.config.get_value(&key).unwrap_or_default(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{key:<28}"), Style::default().fg(Color::Cyan)),
+                Span::styled(format!("{value:<12}"), Style::default().fg(Color::Yellow)),
+                Span::raw(description),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(" Settings ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .bg(Color::Blue)
+                .fg(Color::White),
+        );
+
+    f.render_stateful_widget(list, area, &mut app.settings_state);
 }
 
 fn render_help_screen(f: &mut Frame, area: Rect, _app: &App) {
@@ -880,11 +1305,19 @@ fn render_help_screen(f: &mut Frame, area: Rect, _app: &App) {
         "  a        - Add new alias (or Ctrl+n)",
         "  d        - Delete selected alias",
         "  r        - Reload aliases (or Ctrl+r)",
+        "  u        - Undo last change (or Ctrl+z)",
+        "  y / Y    - Copy command / full alias line",
+        "  PgUp/PgDn - Scroll a page",
+        "  Home/End - Jump to first/last alias",
         "",
         "Search:",
         "  Type to search, ESC to clear",
         "  Field search: name:git, cmd:status, tag:dev",
         "",
+        "Settings:",
+        "  Enter    - Toggle option or edit value",
+        "  ESC      - Back to menu",
+        "",
         "Edit/Add Mode:",
         "  Tab/Shift+Tab - Navigate fields",
         "  Enter         - Save",
@@ -904,6 +1337,24 @@ fn render_help_screen(f: &mut Frame, area: Rect, _app: &App) {
 }
 
 fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
+    if let Some(warning) = app
+        .edit_warning
+        .as_ref()
+        .filter(|_| matches!(app.screen, Screen::EditAlias | Screen::AddAlias))
+    {
+        let status = Paragraph::new(format!(
+            "Warning: {warning} - Enter: save anyway, ESC: cancel"
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red)),
+        )
+        .style(Style::default().fg(Color::Yellow));
+        f.render_widget(status, area);
+        return;
+    }
+
     let status_text = match &app.status_message {
         Some(msg) => msg.clone(),
         None => match app.screen {
@@ -914,7 +1365,7 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
                 if app.search_focused {
                     "Search: type to filter | Field search: name:term, cmd:term, tag:term | ESC to cancel".to_string()
                 } else {
-                    "/ search | e edit | a add | d delete | r reload | Ctrl+f/n/r | ESC menu"
+                    "/ search | e edit | a add | d delete | y/Y copy | u undo | r reload | Ctrl+f/n/r/z | ESC menu"
                         .to_string()
                 }
             }
@@ -927,7 +1378,13 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
                     .to_string()
             }
             Screen::ConfirmDelete => "Confirm delete - y: yes, n/ESC: no".to_string(),
-            Screen::Settings => "Settings - ESC to go back".to_string(),
+            Screen::Settings => {
+                if app.settings_edit.is_some() {
+                    "Edit value - type to change, Enter: save, ESC: cancel".to_string()
+                } else {
+                    "Settings - ↑/↓ navigate, Enter: toggle/edit, ESC: back".to_string()
+                }
+            }
             Screen::Help => "Help screen - Press any key to close".to_string(),
         },
     };
@@ -978,80 +1435,6 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-fn parse_alias_line(line: &str, line_number: usize) -> Option<Alias> {
-    if !line.starts_with("alias ") {
-        return None;
-    }
-
-    let eq_pos = line.find('=')?;
-    let name = line[6..eq_pos].trim().to_string();
-    let rest = &line[eq_pos + 1..];
-
-    let mut command = String::new();
-    let mut note = None;
-    let mut tags = Vec::new();
-
-    let rest = rest.trim();
-    if let Some(stripped) = rest.strip_prefix('\'') {
-        if let Some(end_quote) = stripped.find('\'') {
-            command = stripped[..end_quote].to_string();
-            let remaining = &rest[end_quote + 2..];
-            parse_comments_and_tags(remaining, &mut note, &mut tags);
-        }
-    } else if let Some(stripped) = rest.strip_prefix('"') {
-        if let Some(end_quote) = stripped.find('"') {
-            command = stripped[..end_quote].to_string();
-            let remaining = &rest[end_quote + 2..];
-            parse_comments_and_tags(remaining, &mut note, &mut tags);
-        }
-    } else {
-        let mut end = rest.len();
-        for (i, ch) in rest.char_indices() {
-            if ch == ' ' || ch == '#' {
-                end = i;
-                break;
-            }
-        }
-        command = rest[..end].to_string();
-        if end < rest.len() {
-            parse_comments_and_tags(&rest[end..], &mut note, &mut tags);
-        }
-    }
-
-    Some(Alias {
-        name,
-        command,
-        note,
-        tags,
-        line_number,
-    })
-}
-
-fn parse_comments_and_tags(text: &str, note: &mut Option<String>, tags: &mut Vec<String>) {
-    let text = text.trim();
-    if text.is_empty() {
-        return;
-    }
-
-    if let Some(tags_pos) = text.find("#tags:") {
-        let tags_part = &text[tags_pos + 6..];
-        *tags = tags_part.split(',').map(|s| s.trim().to_string()).collect();
-
-        let note_part = text[..tags_pos].trim();
-        if let Some(stripped) = note_part.strip_prefix('#') {
-            let note_text = stripped.trim();
-            if !note_text.is_empty() {
-                *note = Some(note_text.to_string());
-            }
-        }
-    } else if let Some(stripped) = text.strip_prefix('#') {
-        let note_text = stripped.trim();
-        if !note_text.is_empty() {
-            *note = Some(note_text.to_string());
-        }
-    }
-}
-
 fn parse_search_query(query: &str) -> Option<(&str, String)> {
     if let Some(colon_pos) = query.find(':') {
         let field = &query[..colon_pos];
@@ -1105,3 +1488,104 @@ fn highlight_text(text: &str, search: &str) -> Vec<Span<'static>> {
         spans
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+    use std::fs;
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_edit_alias_input(KeyCode::Char(c)).unwrap();
+        }
+    }
+
+    #[test]
+    fn suspicious_command_warns_before_saving() {
+        let home = FakeHome::new("interactive-warning");
+        let aliases = home.write("aliases", "alias ll='ls -la'\n");
+        let mut app = App::new().unwrap();
+        app.screen = Screen::AddAlias;
+
+        type_text(&mut app, "wipe");
+        app.handle_edit_alias_input(KeyCode::Tab).unwrap();
+        type_text(&mut app, "rm -rf /tmp/build");
+        app.handle_edit_alias_input(KeyCode::Enter).unwrap();
+
+        let warning = app.edit_warning.clone().unwrap();
+        assert!(warning.contains("dangerous"), "{warning}");
+        assert_eq!(app.screen, Screen::AddAlias);
+        assert_eq!(fs::read_to_string(&aliases).unwrap(), "alias ll='ls -la'\n");
+
+        app.handle_edit_alias_input(KeyCode::Enter).unwrap();
+        assert!(app.edit_warning.is_none());
+        assert_eq!(app.screen, Screen::AliasBrowser);
+        assert!(fs::read_to_string(&aliases)
+            .unwrap()
+            .contains("alias wipe='rm -rf /tmp/build'"));
+    }
+
+    #[test]
+    fn delete_finds_the_alias_after_the_file_shifted() {
+        let home = FakeHome::new("interactive-shifted");
+        let aliases = home.write("aliases", "alias a='echo a'\nalias b='echo b'\n");
+        let mut app = App::new().unwrap();
+        home.write(
+            "aliases",
+            "alias new='echo new'\nalias a='echo a'\nalias b='echo b'\n",
+        );
+
+        app.delete_alias(1).unwrap();
+
+        let content = fs::read_to_string(&aliases).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines, ["alias new='echo new'", "alias a='echo a'"]);
+    }
+
+    #[test]
+    fn edit_of_an_alias_removed_on_disk_is_refused() {
+        let home = FakeHome::new("interactive-removed");
+        home.write("aliases", "alias a='echo a'\nalias b='echo b'\n");
+        let mut app = App::new().unwrap();
+        let aliases = home.write("aliases", "alias a='echo a'\n");
+        app.edit_name = "b".to_string();
+        app.edit_command = "echo changed".to_string();
+
+        app.save_edit_alias(1).unwrap();
+
+        assert_eq!(fs::read_to_string(&aliases).unwrap(), "alias a='echo a'\n");
+        assert!(app.status_message.unwrap().contains("'b' was removed"));
+        assert_eq!(app.aliases.len(), 1);
+    }
+
+    #[test]
+    fn delete_keeps_the_trailing_newline_and_undo_restores_it() {
+        let home = FakeHome::new("interactive-undo");
+        let original = "alias a='echo a'\nalias b='echo b'\n";
+        let aliases = home.write("aliases", original);
+        let mut app = App::new().unwrap();
+
+        app.delete_alias(0).unwrap();
+        assert_eq!(fs::read_to_string(&aliases).unwrap(), "alias b='echo b'\n");
+
+        app.undo().unwrap();
+        assert_eq!(fs::read_to_string(&aliases).unwrap(), original);
+    }
+
+    #[test]
+    fn undo_is_refused_after_an_outside_change() {
+        let home = FakeHome::new("interactive-undo-refused");
+        let aliases = home.write("aliases", "alias a='echo a'\nalias b='echo b'\n");
+        let mut app = App::new().unwrap();
+        app.delete_alias(0).unwrap();
+        let changed = "alias b='echo b'\nalias c='echo c'\n";
+        home.write("aliases", changed);
+
+        app.undo().unwrap();
+
+        assert_eq!(fs::read_to_string(&aliases).unwrap(), changed);
+        assert!(app.status_message.unwrap().starts_with("Cannot undo"));
+        assert_eq!(app.aliases.len(), 2);
+    }
+}
@@ -1,25 +1,233 @@
-use crate::utils::get_aliases_path;
-use std::fs;
+use crate::alias::{parse_content, Alias};
+use crate::commands::config::load_config;
+use crate::utils::{read_aliases_sources, read_usage, OutputFormat};
+use std::path::Path;
 
-pub fn list_aliases(tag: Option<&str>) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
-    let contents = fs::read_to_string(&aliases_path)?;
+#[derive(Debug)]
+pub enum SortOrder {
+    Name,
+    Command,
+    Recent,
+    Used,
+}
+
+impl std::str::FromStr for SortOrder {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(SortOrder::Name),
+            "command" => Ok(SortOrder::Command),
+            "recent" => Ok(SortOrder::Recent),
+            "used" => Ok(SortOrder::Used),
+            _ => anyhow::bail!(
+                "Unsupported sort order: {}. Supported: name, command, recent, used",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListColumn {
+    Name,
+    Command,
+    Note,
+    Tags,
+    Source,
+}
+
+impl ListColumn {
+    fn header(&self) -> &'static str {
+        match self {
+            ListColumn::Name => "NAME",
+            ListColumn::Command => "COMMAND",
+            ListColumn::Note => "NOTE",
+            ListColumn::Tags => "TAGS",
+            ListColumn::Source => "SOURCE",
+        }
+    }
+}
+
+impl std::str::FromStr for ListColumn {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "name" => Ok(ListColumn::Name),
+            "command" => Ok(ListColumn::Command),
+            "note" => Ok(ListColumn::Note),
+            "tags" => Ok(ListColumn::Tags),
+            "source" => Ok(ListColumn::Source),
+            _ => anyhow::bail!(
+                "Unsupported column: {}. Supported: name, command, note, tags, source",
+                s
+            ),
+        }
+    }
+}
+
+pub fn list_aliases(
+    tag: Option<&str>,
+    show_sources: bool,
+    sort: SortOrder,
+    reverse: bool,
+    names_only: bool,
+    columns: &[ListColumn],
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    let sources = read_aliases_sources()?;
+
+    if sources.is_empty() && output == OutputFormat::Text {
+        println!("No aliases file found.");
+        return Ok(());
+    }
+
+    let mut aliases: Vec<(&Path, Alias)> = sources
+        .iter()
+        .flat_map(|(path, content)| {
+            parse_content(content)
+                .into_iter()
+                .map(move |alias| (path.as_path(), alias))
+        })
+        .filter(|(_, alias)| tag.is_none_or(|tag| alias.tags.iter().any(|t| t == tag)))
+        .collect();
+
+    if aliases.is_empty() && output == OutputFormat::Text {
+        match tag {
+            Some(tag) => println!("No aliases found with tag: {tag}"),
+            None => println!("No aliases found."),
+        }
+        return Ok(());
+    }
 
-    if let Some(tag) = tag {
-        let filtered: Vec<&str> = contents
-            .lines()
-            .filter(|line| line.contains(&format!("#tags:{tag}")))
+    match sort {
+        SortOrder::Name => aliases.sort_by(|a, b| a.1.name.cmp(&b.1.name)),
+        SortOrder::Command => aliases.sort_by(|a, b| a.1.command.cmp(&b.1.command)),
+        SortOrder::Recent => aliases.reverse(),
+        SortOrder::Used => {
+            let usage = read_usage()?;
+            let count = |name: &str| usage.aliases.get(name).map_or(0, |u| u.count);
+            aliases.sort_by(|a, b| {
+                count(&b.1.name)
+                    .cmp(&count(&a.1.name))
+                    .then_with(|| a.1.name.cmp(&b.1.name))
+            });
+        }
+    }
+    if reverse {
+        aliases.reverse();
+    }
+
+    if output == OutputFormat::Json {
+        let value: Vec<serde_json::Value> = aliases
+            .iter()
+            .map(|(path, alias)| {
+                if names_only {
+                    serde_json::json!(alias.name)
+                } else {
+                    serde_json::json!({
+                        "name": alias.name,
+                        "command": alias.command,
+                        "note": alias.note,
+                        "tags": alias.tags,
+                        "category": alias.category,
+                        "source": path.display().to_string(),
+                    })
+                }
+            })
             .collect();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
 
-        if filtered.is_empty() {
-            println!("No aliases found with tag: {tag}");
-        } else {
-            for alias in filtered {
-                println!("{alias}");
-            }
+    if names_only {
+        for (_, alias) in &aliases {
+            println!("{}", alias.name);
         }
+        return Ok(());
+    }
+
+    let display = load_config().display;
+    let rows: Vec<(&Path, &Alias, String)> = aliases
+        .iter()
+        .map(|(path, alias)| {
+            let command = alias.command.replace('\n', "; ");
+            let command = if display.truncate_commands
+                && command.chars().count() > display.max_command_length
+            {
+                let truncated: String = command
+                    .chars()
+                    .take(display.max_command_length.saturating_sub(3))
+                    .collect();
+                format!("{truncated}...")
+            } else {
+                command
+            };
+            (*path, alias, command)
+        })
+        .collect();
+
+    let columns: Vec<ListColumn> = if columns.is_empty() {
+        let mut defaults = vec![
+            ListColumn::Name,
+            ListColumn::Command,
+            ListColumn::Note,
+            ListColumn::Tags,
+        ];
+        if show_sources {
+            defaults.push(ListColumn::Source);
+        }
+        defaults
     } else {
-        println!("{contents}");
+        columns.to_vec()
+    };
+
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|(path, alias, command)| {
+            columns
+                .iter()
+                .map(|column| match column {
+                    ListColumn::Name => alias.name.clone(),
+                    ListColumn::Command => command.clone(),
+                    ListColumn::Note => alias.note.clone().unwrap_or_default(),
+                    ListColumn::Tags => alias.tags.join(","),
+                    ListColumn::Source => format!("[{}]", path.display()),
+                })
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(column.header().len())
+        })
+        .collect();
+
+    let format_row = |values: Vec<&str>| -> String {
+        let line = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        line.trim_end().to_string()
+    };
+
+    let header = format_row(columns.iter().map(ListColumn::header).collect());
+    println!("{header}");
+    println!("{}", "-".repeat(header.chars().count()));
+
+    for row in &cells {
+        println!("{}", format_row(row.iter().map(String::as_str).collect()));
     }
 
     Ok(())
@@ -0,0 +1,77 @@
+use crate::alias::{parse_content, Alias};
+use crate::utils::{get_aliases_path, read_aliases_file, OutputFormat};
+
+pub fn alias_exists(
+    alias: &str,
+    print_command: bool,
+    output: OutputFormat,
+) -> anyhow::Result<bool> {
+    let found = find_alias(alias)?;
+
+    if output == OutputFormat::Json {
+        let value = serde_json::json!({
+            "name": alias,
+            "exists": found.is_some(),
+            "command": found.as_ref().map(|a| &a.command),
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(found.is_some());
+    }
+
+    match found {
+        Some(alias_data) => {
+            if print_command {
+                println!("{}", alias_data.command);
+            }
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+pub fn get_alias(alias: &str, quiet: bool, format: OutputFormat) -> anyhow::Result<bool> {
+    let Some(alias_data) = find_alias(alias)? else {
+        if !quiet {
+            eprintln!("Alias '{alias}' not found.");
+        }
+        return Ok(false);
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let value = serde_json::json!({
+                "name": alias_data.name,
+                "command": alias_data.command,
+                "note": alias_data.note,
+                "tags": alias_data.tags,
+                "category": alias_data.category,
+            });
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        OutputFormat::Text if quiet => println!("{}", alias_data.command),
+        OutputFormat::Text => {
+            println!("{} -> {}", alias_data.name, alias_data.command);
+            if let Some(note) = &alias_data.note {
+                println!("Note: {note}");
+            }
+            if !alias_data.tags.is_empty() {
+                println!("Tags: {}", alias_data.tags.join(", "));
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+fn find_alias(alias: &str) -> anyhow::Result<Option<Alias>> {
+    let aliases_path = get_aliases_path()?;
+
+    if !aliases_path.exists() {
+        return Ok(None);
+    }
+
+    let content = read_aliases_file(&aliases_path)?;
+    Ok(parse_content(&content)
+        .into_iter()
+        .find(|a| a.name == alias))
+}
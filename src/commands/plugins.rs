@@ -1,10 +1,17 @@
+use crate::alias::{parse_content, Alias};
+use crate::commands::config::load_config;
+use crate::utils::{read_aliases_file, OutputFormat};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fs,
+    io::{self, Write},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
 };
+use which::which;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Plugin {
@@ -46,10 +53,10 @@ pub struct PluginManifest {
     pub config_schema: HashMap<String, serde_json::Value>,
 }
 
-pub fn list_plugins(show_all: bool) -> anyhow::Result<()> {
+pub fn list_plugins(show_all: bool, output: OutputFormat) -> anyhow::Result<()> {
     let plugins = load_plugins()?;
 
-    if plugins.is_empty() {
+    if plugins.is_empty() && output == OutputFormat::Text {
         println!("No plugins installed");
         println!("Install plugins with 'shorty plugin install <name>'");
         return Ok(());
@@ -61,6 +68,26 @@ pub fn list_plugins(show_all: bool) -> anyhow::Result<()> {
         plugins.iter().filter(|p| p.enabled).collect()
     };
 
+    if output == OutputFormat::Json {
+        let value: Vec<serde_json::Value> = filtered_plugins
+            .iter()
+            .map(|plugin| {
+                serde_json::json!({
+                    "name": plugin.name,
+                    "version": plugin.version,
+                    "description": plugin.description,
+                    "author": plugin.author,
+                    "enabled": plugin.enabled,
+                    "commands": plugin.commands.iter().map(|c| &c.name).collect::<Vec<_>>(),
+                    "hooks": plugin.hooks,
+                    "installed_at": plugin.installed_at,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
     if filtered_plugins.is_empty() {
         println!(
             "No {} plugins found",
@@ -168,7 +195,7 @@ pub fn remove_plugin(name: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn enable_plugin(name: &str) -> anyhow::Result<()> {
+pub fn enable_plugin(name: &str, skip_deps: bool) -> anyhow::Result<()> {
     let mut plugins = load_plugins()?;
 
     let plugin = plugins
@@ -183,6 +210,17 @@ pub fn enable_plugin(name: &str) -> anyhow::Result<()> {
 
     validate_plugin(plugin)?;
 
+    if !skip_deps {
+        let missing = missing_dependencies(plugin)?;
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Plugin '{}' requires commands that are not on PATH: {}. Install them or use --skip-deps",
+                name,
+                missing.join(", ")
+            );
+        }
+    }
+
     plugin.enabled = true;
     save_plugins(&plugins)?;
 
@@ -263,10 +301,87 @@ pub fn show_plugin(name: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+pub fn doctor_plugins() -> anyhow::Result<()> {
+    let plugins = load_plugins()?;
+
+    if plugins.is_empty() {
+        println!("No plugins installed");
+        return Ok(());
+    }
+
+    doctor_report(&plugins, &mut io::stdout().lock())
+}
+
+fn doctor_report(plugins: &[Plugin], out: &mut impl Write) -> anyhow::Result<()> {
+    writeln!(out, "Checking {} plugin(s)...\n", plugins.len())?;
+
+    let mut healthy = 0;
+    let mut enabled_broken = 0;
+    let mut disabled_broken = 0;
+
+    for plugin in plugins {
+        let status = if plugin.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        };
+
+        match validate_plugin(plugin) {
+            Ok(()) => {
+                healthy += 1;
+                writeln!(
+                    out,
+                    "[OK]     {} v{} ({status})",
+                    plugin.name, plugin.version
+                )?;
+            }
+            Err(e) => {
+                if plugin.enabled {
+                    enabled_broken += 1;
+                } else {
+                    disabled_broken += 1;
+                }
+                writeln!(
+                    out,
+                    "[BROKEN] {} v{} ({status})",
+                    plugin.name, plugin.version
+                )?;
+                writeln!(out, "   {e}")?;
+            }
+        }
+
+        if let Some(drift) = manifest_drift(plugin)? {
+            writeln!(out, "   Warning: {drift}")?;
+        }
+
+        if let Ok(missing) = missing_dependencies(plugin) {
+            if !missing.is_empty() {
+                writeln!(
+                    out,
+                    "   Warning: missing dependencies: {}",
+                    missing.join(", ")
+                )?;
+            }
+        }
+    }
+
+    writeln!(
+        out,
+        "\n{healthy} healthy, {enabled_broken} enabled but broken, {disabled_broken} disabled and broken"
+    )?;
+
+    if enabled_broken > 0 {
+        writeln!(out, "Reinstall broken plugins with 'shorty plugin install <path>' or disable them with 'shorty plugin disable <name>'")?;
+    }
+
+    Ok(())
+}
+
 pub fn execute_plugin_command(
     plugin_name: &str,
     command: &str,
     args: &[String],
+    timeout: Option<u64>,
 ) -> anyhow::Result<()> {
     let plugins = load_plugins()?;
 
@@ -289,35 +404,38 @@ pub fn execute_plugin_command(
 
     println!("Executing plugin command: {plugin_name} {command}");
 
-    let plugin_path = get_plugin_path(&plugin.name)?;
-    let executable_path = plugin_path.join(&plugin.executable);
-
-    if !executable_path.exists() {
-        anyhow::bail!("Plugin executable not found: {}", executable_path.display());
-    }
-
-    let mut cmd = Command::new(&executable_path);
+    let mut cmd = plugin_command(plugin)?;
     cmd.arg(command);
     cmd.args(args);
 
-    cmd.env("SHORTY_PLUGIN_NAME", &plugin.name);
-    cmd.env("SHORTY_PLUGIN_VERSION", &plugin.version);
-    cmd.env(
-        "SHORTY_ALIASES_PATH",
-        get_aliases_path().display().to_string(),
-    );
-
-    let output = cmd.output()?;
-
-    if !output.status.success() {
-        println!("Plugin command failed:");
-        println!("{}", String::from_utf8_lossy(&output.stderr));
-        return Ok(());
-    }
+    let timeout_secs = timeout.unwrap_or_else(|| load_config().plugins.timeout_secs);
+    let mut child = cmd
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if timeout_secs > 0 && started.elapsed() >= Duration::from_secs(timeout_secs) {
+            child.kill()?;
+            child.wait()?;
+            anyhow::bail!(
+                "Plugin command timed out after {}s and was killed",
+                timeout_secs
+            );
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    if !stdout.is_empty() {
-        println!("{stdout}");
+    if !status.success() {
+        match status.code() {
+            Some(code) => anyhow::bail!("Plugin command failed with exit code {}", code),
+            None => anyhow::bail!("Plugin command was terminated by a signal"),
+        }
     }
 
     println!("Plugin command completed successfully");
@@ -325,8 +443,35 @@ pub fn execute_plugin_command(
     Ok(())
 }
 
-#[allow(dead_code)]
-pub fn run_plugin_hooks(hook_name: &str, context: &HashMap<String, String>) -> anyhow::Result<()> {
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    hook: &'a str,
+    context: &'a HashMap<String, String>,
+    alias: Option<&'a Alias>,
+    aliases: Vec<Alias>,
+}
+
+/// Runs `hook_name` for every enabled plugin that declares it.
+///
+/// Each plugin is invoked as `<executable> --hook <hook_name>`, with every
+/// context entry exported as `SHORTY_HOOK_<KEY>` and this JSON written to stdin:
+///
+/// ```json
+/// {
+///   "hook": "post_add",
+///   "context": { "alias": "gs", "command": "git status" },
+///   "alias": { "name": "gs", "command": "git status", "note": null, "tags": [] },
+///   "aliases": [{ "name": "...", "command": "...", "note": null, "tags": [] }]
+/// }
+/// ```
+///
+/// `alias` is the alias the hook is about (null if none) and `aliases` holds
+/// every alias in the primary aliases file at the time the hook runs.
+pub fn run_plugin_hooks(
+    hook_name: &str,
+    context: &HashMap<String, String>,
+    alias: Option<&Alias>,
+) -> anyhow::Result<()> {
     let plugins = load_plugins()?;
 
     let hook_plugins: Vec<_> = plugins
@@ -338,8 +483,29 @@ pub fn run_plugin_hooks(hook_name: &str, context: &HashMap<String, String>) -> a
         return Ok(());
     }
 
+    let aliases_path = get_aliases_path()?;
+    let aliases = if aliases_path.exists() {
+        parse_content(&read_aliases_file(&aliases_path)?)
+    } else {
+        Vec::new()
+    };
+    let payload = serde_json::to_vec(&HookPayload {
+        hook: hook_name,
+        context,
+        alias,
+        aliases,
+    })?;
+
     for plugin in hook_plugins {
-        if let Err(e) = execute_plugin_hook(plugin, hook_name, context) {
+        if let Err(e) = execute_plugin_hook(plugin, hook_name, context, &payload) {
+            if hook_name.starts_with("pre_") {
+                anyhow::bail!(
+                    "Operation aborted by {} hook of plugin '{}': {}",
+                    hook_name,
+                    plugin.name,
+                    e
+                );
+            }
             eprintln!("Hook execution failed for plugin '{}': {}", plugin.name, e);
         }
     }
@@ -347,12 +513,15 @@ pub fn run_plugin_hooks(hook_name: &str, context: &HashMap<String, String>) -> a
     Ok(())
 }
 
-#[allow(dead_code)]
-fn execute_plugin_hook(
-    plugin: &Plugin,
-    hook_name: &str,
-    context: &HashMap<String, String>,
-) -> anyhow::Result<()> {
+pub fn run_alias_hooks(hook_name: &str, alias: &Alias) -> anyhow::Result<()> {
+    let context = HashMap::from([
+        ("alias".to_string(), alias.name.clone()),
+        ("command".to_string(), alias.command.clone()),
+    ]);
+    run_plugin_hooks(hook_name, &context, Some(alias))
+}
+
+fn plugin_command(plugin: &Plugin) -> anyhow::Result<Command> {
     let plugin_path = get_plugin_path(&plugin.name)?;
     let executable_path = plugin_path.join(&plugin.executable);
 
@@ -361,27 +530,81 @@ fn execute_plugin_hook(
     }
 
     let mut cmd = Command::new(&executable_path);
-    cmd.arg("--hook");
-    cmd.arg(hook_name);
+    cmd.current_dir(&plugin_path);
 
     cmd.env("SHORTY_PLUGIN_NAME", &plugin.name);
     cmd.env("SHORTY_PLUGIN_VERSION", &plugin.version);
     cmd.env(
         "SHORTY_ALIASES_PATH",
-        get_aliases_path().display().to_string(),
+        get_aliases_path()?.display().to_string(),
     );
 
+    for (key, value) in &plugin.config {
+        let key: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        cmd.env(format!("SHORTY_PLUGIN_CONFIG_{key}"), value);
+    }
+
+    Ok(cmd)
+}
+
+fn execute_plugin_hook(
+    plugin: &Plugin,
+    hook_name: &str,
+    context: &HashMap<String, String>,
+    payload: &[u8],
+) -> anyhow::Result<()> {
+    let mut cmd = plugin_command(plugin)?;
+    cmd.arg("--hook");
+    cmd.arg(hook_name);
+
     for (key, value) in context {
         cmd.env(format!("SHORTY_HOOK_{}", key.to_uppercase()), value);
     }
 
-    let output = cmd.output()?;
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Feed stdin from a separate thread so a hook that writes a lot of output
+    // before reading its input cannot deadlock against us.
+    let stdin = child.stdin.take();
+    let output = thread::scope(|scope| -> anyhow::Result<_> {
+        let writer = scope.spawn(move || match stdin {
+            Some(mut stdin) => stdin.write_all(payload),
+            None => Ok(()),
+        });
+        let output = child.wait_with_output()?;
+        match writer.join().expect("plugin stdin writer panicked") {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.into()),
+            _ => Ok(output),
+        }
+    })?;
 
     if !output.status.success() {
-        anyhow::bail!(
-            "Hook execution failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
+        let status = match output.status.code() {
+            Some(code) => format!("exited with code {code}"),
+            None => "terminated by a signal".to_string(),
+        };
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.trim().is_empty() {
+            anyhow::bail!("hook {}", status);
+        }
+        anyhow::bail!("hook {}: {}", status, stderr.trim());
     }
 
     Ok(())
@@ -464,6 +687,52 @@ fn validate_plugin(plugin: &Plugin) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn missing_dependencies(plugin: &Plugin) -> anyhow::Result<Vec<String>> {
+    let manifest_path = get_plugin_path(&plugin.name)?.join("plugin.toml");
+    if !manifest_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let manifest: PluginManifest = toml::from_str(&fs::read_to_string(&manifest_path)?)?;
+    Ok(manifest
+        .dependencies
+        .into_iter()
+        .filter(|dependency| which(dependency).is_err())
+        .collect())
+}
+
+fn manifest_drift(plugin: &Plugin) -> anyhow::Result<Option<String>> {
+    let manifest_path = get_plugin_path(&plugin.name)?.join("plugin.toml");
+
+    if !manifest_path.exists() {
+        return Ok(Some(format!(
+            "Plugin manifest not found: {}",
+            manifest_path.display()
+        )));
+    }
+
+    let manifest: PluginManifest = match toml::from_str(&fs::read_to_string(&manifest_path)?) {
+        Ok(manifest) => manifest,
+        Err(e) => return Ok(Some(format!("Plugin manifest is invalid: {e}"))),
+    };
+
+    if manifest.version != plugin.version {
+        return Ok(Some(format!(
+            "Manifest version {} differs from installed version {}",
+            manifest.version, plugin.version
+        )));
+    }
+
+    if manifest.executable != plugin.executable {
+        return Ok(Some(format!(
+            "Manifest executable '{}' differs from installed executable '{}'",
+            manifest.executable, plugin.executable
+        )));
+    }
+
+    Ok(None)
+}
+
 fn validate_manifest(manifest: &PluginManifest) -> anyhow::Result<()> {
     if manifest.name.is_empty() {
         anyhow::bail!("Plugin name cannot be empty");
@@ -540,7 +809,7 @@ fn get_plugins_registry_path() -> anyhow::Result<PathBuf> {
     Ok(home_dir.join(".shorty").join("plugins.toml"))
 }
 
-fn get_aliases_path() -> PathBuf {
+fn get_aliases_path() -> anyhow::Result<PathBuf> {
     crate::utils::get_aliases_path()
 }
 
@@ -577,3 +846,143 @@ fn is_executable(path: &Path) -> anyhow::Result<bool> {
         Ok(path.extension().is_some_and(|ext| ext == "exe"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::add::{add_alias, AddOptions};
+    use crate::utils::test_support::FakeHome;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn script_plugin(name: &str, script: &str, hooks: &[&str]) -> Plugin {
+        let dir = get_plugin_path(name).unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        let executable = dir.join("plugin.sh");
+        fs::write(&executable, format!("#!/bin/sh\n{script}")).unwrap();
+        fs::set_permissions(&executable, fs::Permissions::from_mode(0o755)).unwrap();
+
+        Plugin {
+            name: name.to_string(),
+            version: "0.1.0".to_string(),
+            description: "test plugin".to_string(),
+            author: "tests".to_string(),
+            enabled: true,
+            executable: "plugin.sh".to_string(),
+            commands: Vec::new(),
+            hooks: hooks.iter().map(|h| h.to_string()).collect(),
+            config: HashMap::new(),
+            installed_at: "2024-01-01 00:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn shell_plugin_hooks_run_around_add() {
+        let home = FakeHome::new("plugin-hooks");
+        let log = home.path().join("hooks.log");
+        let plugin = script_plugin(
+            "logger",
+            &format!(
+                "printf '%s %s\\n' \"$2\" \"$SHORTY_HOOK_ALIAS\" >> {}\ncat >> {}\necho >> {}\n",
+                log.display(),
+                log.display(),
+                log.display()
+            ),
+            &["pre_add", "post_add"],
+        );
+        save_plugins(&[plugin]).unwrap();
+
+        add_alias("gs", "git status", &None, &[], &AddOptions::default()).unwrap();
+
+        let lines: Vec<String> = fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        assert_eq!(lines[0], "pre_add gs");
+        assert_eq!(lines[2], "post_add gs");
+        let payload: serde_json::Value = serde_json::from_str(&lines[3]).unwrap();
+        assert_eq!(payload["hook"], "post_add");
+        assert_eq!(payload["alias"]["command"], "git status");
+        assert_eq!(payload["aliases"][0]["name"], "gs");
+    }
+
+    #[test]
+    fn failing_pre_hook_aborts_the_operation() {
+        let home = FakeHome::new("plugin-veto");
+        let plugin = script_plugin("veto", "echo 'not today' >&2\nexit 3\n", &["pre_add"]);
+        save_plugins(&[plugin]).unwrap();
+
+        let error = add_alias("gs", "git status", &None, &[], &AddOptions::default()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Operation aborted by pre_add hook of plugin 'veto': hook exited with code 3: not today"
+        );
+        assert!(!home.path().join(".shorty").join("aliases").exists());
+    }
+
+    #[test]
+    fn plugin_command_exports_config_and_runs_in_the_plugin_dir() {
+        let _home = FakeHome::new("plugin-env");
+        let mut plugin = script_plugin(
+            "greeter",
+            "echo \"$SHORTY_PLUGIN_CONFIG_GREETING_TEXT $SHORTY_PLUGIN_CONFIG_RETRIES\"\npwd\n",
+            &[],
+        );
+        plugin.config = HashMap::from([
+            ("greeting-text".to_string(), serde_json::json!("hello")),
+            ("retries".to_string(), serde_json::json!(3)),
+        ]);
+
+        let output = plugin_command(&plugin).unwrap().output().unwrap();
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines[0], "hello 3");
+        assert_eq!(
+            fs::canonicalize(lines[1]).unwrap(),
+            fs::canonicalize(get_plugin_path("greeter").unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn hook_writing_output_before_reading_stdin_does_not_deadlock() {
+        let home = FakeHome::new("plugin-large-io");
+        let received = home.path().join("received");
+        let plugin = script_plugin(
+            "chatty",
+            &format!("head -c 262144 /dev/zero\ncat > {}\n", received.display()),
+            &[],
+        );
+        let payload = vec![b'x'; 262144];
+
+        execute_plugin_hook(&plugin, "post_add", &HashMap::new(), &payload).unwrap();
+        assert_eq!(fs::read(&received).unwrap().len(), payload.len());
+    }
+
+    #[test]
+    fn doctor_reports_healthy_and_broken_plugins() {
+        let _home = FakeHome::new("plugin-doctor");
+        let healthy = script_plugin("good", "exit 0\n", &[]);
+        let mut broken = script_plugin("bad", "exit 0\n", &[]);
+        broken.executable = "missing.sh".to_string();
+
+        let mut out = Vec::new();
+        doctor_report(&[healthy, broken], &mut out).unwrap();
+
+        let report = String::from_utf8(out).unwrap();
+        assert!(
+            report.contains("[OK]     good v0.1.0 (enabled)"),
+            "{report}"
+        );
+        assert!(report.contains("[BROKEN] bad v0.1.0 (enabled)"), "{report}");
+        assert!(
+            report.contains("   Plugin executable not found: "),
+            "{report}"
+        );
+        assert!(
+            report.contains("1 healthy, 1 enabled but broken, 0 disabled and broken"),
+            "{report}"
+        );
+    }
+}
@@ -1,21 +1,324 @@
-use crate::utils::get_aliases_path;
-use std::fs;
-
-pub fn remove_alias(alias: &str) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
-    let contents = fs::read_to_string(&aliases_path)?;
-    let mut new_contents: String = contents
-        .lines()
-        .filter(|line| !line.starts_with(&format!("alias {alias}=")))
-        .collect::<Vec<_>>()
-        .join("\n");
+use crate::alias::{function_block_name, parse_content, parse_function_block, Alias};
+use crate::commands::backup::backup_before_edit;
+use crate::commands::history::record_operation;
+use crate::commands::plugins::run_alias_hooks;
+use crate::utils::{acquire_lock, get_aliases_path, read_aliases_file, write_aliases_atomic};
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+struct Removal<'a> {
+    kept: Vec<&'a str>,
+    removed: Vec<(usize, &'a str)>,
+    definitions: usize,
+}
+
+fn plan_removal<'a>(lines: &[&'a str], names: &[&str]) -> Removal<'a> {
+    let mut removal = Removal {
+        kept: Vec::new(),
+        removed: Vec::new(),
+        definitions: 0,
+    };
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let line_count = if names
+            .iter()
+            .any(|name| line.starts_with(&format!("alias {name}=")))
+        {
+            1
+        } else if function_block_name(line).is_some_and(|name| names.contains(&name)) {
+            parse_function_block(&lines[i..]).map_or(1, |block| block.line_count)
+        } else {
+            removal.kept.push(line);
+            i += 1;
+            continue;
+        };
+
+        removal.definitions += 1;
+        for (offset, removed_line) in lines[i..i + line_count].iter().enumerate() {
+            removal.removed.push((i + offset + 1, removed_line));
+        }
+        i += line_count;
+    }
+
+    removal
+}
+
+pub fn remove_alias(alias: &str, dry_run: bool) -> anyhow::Result<()> {
+    let aliases_path = get_aliases_path()?;
+    if !aliases_path.exists() {
+        anyhow::bail!("Alias '{}' not found", alias);
+    }
+
+    let contents = read_aliases_file(&aliases_path)?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let removal = plan_removal(&lines, &[alias]);
+
+    if removal.definitions == 0 {
+        anyhow::bail!("Alias '{}' not found", alias);
+    }
+
+    if dry_run || removal.definitions > 1 {
+        println!(
+            "{} {} line(s) for alias '{alias}':",
+            if dry_run {
+                "Would remove"
+            } else {
+                "This will remove"
+            },
+            removal.removed.len()
+        );
+        for (line_number, line) in &removal.removed {
+            println!("  {line_number}: {line}");
+        }
+    }
+
+    if dry_run {
+        println!("Dry run: no changes were made.");
+        return Ok(());
+    }
+
+    if removal.definitions > 1 {
+        print!(
+            "Alias '{alias}' is defined {} times. Remove all of them? (y/n): ",
+            removal.definitions
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Operation aborted.");
+            return Ok(());
+        }
+    }
+
+    let removed_alias = parse_content(&contents)
+        .into_iter()
+        .find(|existing| existing.name == alias)
+        .unwrap_or_else(|| Alias {
+            name: alias.to_string(),
+            ..Alias::default()
+        });
+    run_alias_hooks("pre_remove", &removed_alias)?;
+
+    let lock = acquire_lock()?;
+    let contents = read_aliases_file(&aliases_path)?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let removal = plan_removal(&lines, &[alias]);
+
+    if removal.definitions == 0 {
+        anyhow::bail!("Alias '{}' not found", alias);
+    }
+
+    backup_before_edit()?;
+
+    let mut new_contents = removal.kept.join("\n");
 
     if !new_contents.ends_with('\n') {
         new_contents.push('\n');
     }
 
-    fs::write(&aliases_path, new_contents)?;
+    write_aliases_atomic(&new_contents)?;
+    drop(lock);
+
+    record_operation("remove", alias);
+    run_alias_hooks("post_remove", &removed_alias)?;
     println!("Removed alias: {alias}");
 
     Ok(())
 }
+
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+            }
+            Some(&c) if c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn select_aliases(aliases: &[Alias], patterns: &[String], tag: Option<&str>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    aliases
+        .iter()
+        .filter(|alias| {
+            patterns
+                .iter()
+                .any(|pattern| glob_match(pattern, &alias.name))
+                || tag.is_some_and(|tag| alias.tags.iter().any(|t| t == tag))
+        })
+        .filter(|alias| seen.insert(alias.name.clone()))
+        .map(|alias| alias.name.clone())
+        .collect()
+}
+
+pub fn remove_aliases(patterns: &[String], tag: Option<&str>, dry_run: bool) -> anyhow::Result<()> {
+    let aliases_path = get_aliases_path()?;
+    if !aliases_path.exists() {
+        anyhow::bail!("No aliases file found");
+    }
+
+    let contents = read_aliases_file(&aliases_path)?;
+    let aliases = parse_content(&contents);
+    let selected = select_aliases(&aliases, patterns, tag);
+
+    if selected.is_empty() {
+        anyhow::bail!("No aliases matched");
+    }
+
+    println!(
+        "{} {} alias(es):",
+        if dry_run {
+            "Would remove"
+        } else {
+            "This will remove"
+        },
+        selected.len()
+    );
+    for name in &selected {
+        println!("  {name}");
+    }
+
+    if dry_run {
+        println!("Dry run: no changes were made.");
+        return Ok(());
+    }
+
+    print!("Remove these {} aliases? (y/n): ", selected.len());
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if !input.trim().eq_ignore_ascii_case("y") {
+        println!("Operation aborted.");
+        return Ok(());
+    }
+
+    let removed: Vec<Alias> = selected
+        .iter()
+        .filter_map(|name| aliases.iter().find(|alias| alias.name == *name).cloned())
+        .collect();
+    for alias in &removed {
+        run_alias_hooks("pre_remove", alias)?;
+    }
+
+    let lock = acquire_lock()?;
+    let contents = read_aliases_file(&aliases_path)?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let names: Vec<&str> = selected.iter().map(String::as_str).collect();
+    let removal = plan_removal(&lines, &names);
+    if removal.definitions == 0 {
+        anyhow::bail!("No aliases matched");
+    }
+
+    backup_before_edit()?;
+
+    let mut new_contents = removal.kept.join("\n");
+    if !new_contents.ends_with('\n') {
+        new_contents.push('\n');
+    }
+
+    write_aliases_atomic(&new_contents)?;
+    drop(lock);
+
+    record_operation("remove", &selected.join(", "));
+    for alias in &removed {
+        run_alias_hooks("post_remove", alias)?;
+    }
+    println!("Removed {} alias definition(s)", removal.definitions);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+    use std::fs;
+
+    const CONTENT: &str = "alias gs='git status' #tags:git\nalias gp='git push' #tags:git\nalias ll='ls -la'\nalias gs='git status -s'\n";
+
+    #[test]
+    fn selection_matches_patterns_and_tags_once() {
+        let aliases = parse_content(CONTENT);
+        assert_eq!(
+            select_aliases(&aliases, &["g*".to_string()], None),
+            ["gs", "gp"]
+        );
+        assert_eq!(select_aliases(&aliases, &[], Some("git")), ["gs", "gp"]);
+        assert_eq!(
+            select_aliases(&aliases, &["l?".to_string()], Some("git")),
+            ["gs", "gp", "ll"]
+        );
+    }
+
+    #[test]
+    fn removal_counts_every_definition() {
+        let lines: Vec<&str> = CONTENT.lines().collect();
+        let removal = plan_removal(&lines, &["gs"]);
+        assert_eq!(removal.definitions, 2);
+        assert_eq!(
+            removal.kept,
+            ["alias gp='git push' #tags:git", "alias ll='ls -la'"]
+        );
+    }
+
+    #[test]
+    fn dry_run_leaves_the_file_unchanged() {
+        let home = FakeHome::new("remove-dry-run");
+        let path = home.write("aliases", CONTENT);
+
+        remove_aliases(&["g*".to_string()], None, true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), CONTENT);
+    }
+
+    #[test]
+    fn removing_an_alias_takes_a_backup() {
+        let home = FakeHome::new("remove-one");
+        let path = home.write("aliases", CONTENT);
+
+        remove_alias("ll", false).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "alias gs='git status' #tags:git\nalias gp='git push' #tags:git\nalias gs='git status -s'\n"
+        );
+        let backups = fs::read_dir(home.path().join(".shorty").join("backups")).unwrap();
+        assert_eq!(backups.count(), 1);
+    }
+
+    #[test]
+    fn removing_a_missing_alias_reports_it_and_takes_no_backup() {
+        let home = FakeHome::new("remove-missing");
+        let path = home.write("aliases", CONTENT);
+
+        let error = remove_alias("nope", false).unwrap_err();
+        assert_eq!(error.to_string(), "Alias 'nope' not found");
+        assert_eq!(fs::read_to_string(&path).unwrap(), CONTENT);
+        assert!(!home.path().join(".shorty").join("backups").exists());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::alias::parse_entries;
+use crate::commands::backup::backup_before_edit;
+use crate::commands::history::record_operation;
+use crate::utils::{acquire_lock, get_aliases_path, read_aliases_file, write_aliases_atomic};
+use which::which;
+
+pub fn rename_alias(old: &str, new: &str, force: bool) -> anyhow::Result<()> {
+    if new.is_empty() || new.contains(|c: char| c.is_whitespace() || "='\"".contains(c)) {
+        anyhow::bail!("Invalid alias name: '{}'", new);
+    }
+    if old == new {
+        println!("Alias '{old}' already has that name.");
+        return Ok(());
+    }
+    if !force && which(new).is_ok() {
+        anyhow::bail!(
+            "'{}' is a system command; use --force to rename '{}' anyway",
+            new,
+            old
+        );
+    }
+
+    let _lock = acquire_lock()?;
+    let aliases_path = get_aliases_path()?;
+
+    if !aliases_path.exists() {
+        anyhow::bail!("Alias '{}' not found", old);
+    }
+
+    let contents = read_aliases_file(&aliases_path)?;
+    let entries = parse_entries(&contents);
+
+    if entries.iter().any(|entry| entry.alias.name == new) {
+        anyhow::bail!("Alias '{}' already exists", new);
+    }
+
+    let matches: Vec<_> = entries
+        .into_iter()
+        .filter(|entry| entry.alias.name == old)
+        .collect();
+    if matches.is_empty() {
+        anyhow::bail!("Alias '{}' not found", old);
+    }
+
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut new_lines: Vec<String> = Vec::with_capacity(lines.len());
+    let mut i = 0;
+
+    while i < lines.len() {
+        match matches.iter().find(|entry| entry.line_number == i + 1) {
+            Some(entry) => {
+                let mut renamed = entry.clone();
+                renamed.alias.name = new.to_string();
+                new_lines.push(renamed.to_line());
+                i += entry.line_count;
+            }
+            None => {
+                new_lines.push(lines[i].to_string());
+                i += 1;
+            }
+        }
+    }
+
+    let mut new_contents = new_lines.join("\n");
+    new_contents.push('\n');
+
+    backup_before_edit()?;
+    write_aliases_atomic(&new_contents)?;
+    record_operation("rename", &format!("{old} -> {new}"));
+
+    println!("Renamed alias: {old} -> {new}");
+    println!("To apply the changes, please restart your terminal!");
+
+    Ok(())
+}
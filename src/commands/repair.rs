@@ -0,0 +1,47 @@
+use crate::commands::backup::create_backup;
+use crate::commands::history::record_operation;
+use crate::utils::{acquire_lock, get_aliases_path, write_aliases_atomic};
+use std::fs;
+
+pub fn repair_encoding() -> anyhow::Result<()> {
+    let _lock = acquire_lock()?;
+    let aliases_path = get_aliases_path()?;
+
+    if !aliases_path.exists() {
+        println!("No aliases file found. Nothing to repair.");
+        return Ok(());
+    }
+
+    let bytes = fs::read(&aliases_path)?;
+    if std::str::from_utf8(&bytes).is_ok() {
+        println!("Aliases file is valid UTF-8. Nothing to repair.");
+        return Ok(());
+    }
+
+    let mut kept = Vec::new();
+    let mut dropped = Vec::new();
+    for (index, line) in bytes.split(|&b| b == b'\n').enumerate() {
+        match std::str::from_utf8(line) {
+            Ok(line) => kept.push(line),
+            Err(_) => dropped.push(index + 1),
+        }
+    }
+
+    let mut content = kept.join("\n");
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+
+    create_backup(None)?;
+    write_aliases_atomic(&content)?;
+    record_operation("repair", &format!("dropped {} line(s)", dropped.len()));
+
+    let lines: Vec<String> = dropped.iter().map(|line| line.to_string()).collect();
+    println!(
+        "Removed {} line(s) with invalid UTF-8: {}",
+        dropped.len(),
+        lines.join(", ")
+    );
+
+    Ok(())
+}
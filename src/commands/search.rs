@@ -1,102 +1,457 @@
-use crate::utils::get_aliases_path;
-use regex::Regex;
-use std::fs;
+use crate::alias::{parse_content, to_line, Alias};
+use crate::commands::config::{load_config, SearchConfig};
+use crate::utils::{read_aliases_sources, OutputFormat};
+use crossterm::style::Stylize;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use regex::{Regex, RegexBuilder};
+use std::io::{self, IsTerminal, Write};
+use std::ops::Range;
 
-pub fn search_aliases(query: &str, search_in: Option<&str>, use_regex: bool) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
-    let contents = fs::read_to_string(&aliases_path)?;
+#[derive(Debug, Clone, Copy)]
+pub enum MatchMode {
+    All,
+    Any,
+}
+
+impl std::str::FromStr for MatchMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "all" => Ok(MatchMode::All),
+            "any" => Ok(MatchMode::Any),
+            _ => anyhow::bail!("Unsupported match mode: {}. Supported: all, any", s),
+        }
+    }
+}
+
+impl MatchMode {
+    fn combine(self, mut matches: impl Iterator<Item = bool>) -> bool {
+        match self {
+            MatchMode::All => matches.all(|matched| matched),
+            MatchMode::Any => matches.any(|matched| matched),
+        }
+    }
+}
+
+pub fn search_aliases(
+    keywords: &[String],
+    match_mode: MatchMode,
+    search_in: Option<&str>,
+    use_regex: bool,
+    show_context: bool,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    let sources = read_aliases_sources()?;
+    let config = load_config();
+    let color_output = config.display.color_output;
+    let config = config.search;
 
-    let regex = if use_regex {
-        Some(Regex::new(query)?)
+    let build_regex = |pattern: &str| {
+        RegexBuilder::new(pattern)
+            .case_insensitive(!config.case_sensitive)
+            .build()
+    };
+    let regexes = if use_regex {
+        keywords
+            .iter()
+            .map(|keyword| build_regex(keyword))
+            .collect::<Result<Vec<_>, _>>()?
     } else {
+        Vec::new()
+    };
+    let matcher = if use_regex {
         None
+    } else {
+        fuzzy_matcher(&config)
     };
 
-    let results: Vec<&str> = contents
-        .lines()
-        .filter(|line| {
-            if line.trim().is_empty() || line.trim().starts_with('#') {
-                return false;
-            }
-
-            if let Some(ref regex) = regex {
-                regex.is_match(line)
-            } else if let Some(field) = search_in {
-                search_in_field(line, query, field)
-            } else {
-                line.contains(query)
-            }
-        })
+    let aliases: Vec<Alias> = sources
+        .iter()
+        .flat_map(|(_, content)| parse_content(content))
         .collect();
 
-    if results.is_empty() {
+    let results = find_matches(
+        &aliases,
+        keywords,
+        match_mode,
+        search_in,
+        &regexes,
+        matcher.as_ref(),
+        &config,
+    );
+
+    if output == OutputFormat::Json {
+        let value: Vec<serde_json::Value> = results
+            .iter()
+            .map(|alias| {
+                serde_json::json!({
+                    "name": alias.name,
+                    "command": alias.command,
+                    "note": alias.note,
+                    "tags": alias.tags,
+                    "category": alias.category,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else if results.is_empty() {
         let search_desc = match search_in {
             Some(field) => format!(" in field '{field}'"),
             None => String::new(),
         };
         let regex_desc = if use_regex { " (regex)" } else { "" };
-        println!("No aliases found matching: '{query}'{search_desc}{regex_desc}");
+        let quoted: Vec<String> = keywords.iter().map(|k| format!("'{k}'")).collect();
+        println!(
+            "No aliases found matching: {}{search_desc}{regex_desc}",
+            quoted.join(match match_mode {
+                MatchMode::All => " and ",
+                MatchMode::Any => " or ",
+            })
+        );
     } else {
+        let highlight = if color_output && io::stdout().is_terminal() {
+            Some(match &matcher {
+                Some(matcher) => Highlight::Fuzzy(matcher, keywords),
+                None => {
+                    let patterns: Vec<String> = keywords
+                        .iter()
+                        .map(|keyword| {
+                            let pattern = if use_regex {
+                                keyword.clone()
+                            } else {
+                                regex::escape(keyword)
+                            };
+                            format!("(?:{pattern})")
+                        })
+                        .collect();
+                    Highlight::Pattern(build_regex(&patterns.join("|"))?)
+                }
+            })
+        } else {
+            None
+        };
+        let paint = |text: &str| match &highlight {
+            Some(highlight) => highlight.apply(text),
+            None => text.to_string(),
+        };
+
         println!("Found {} matching alias(es):", results.len());
         for alias in results {
-            println!("{alias}");
+            if show_context {
+                write_alias_context(&mut io::stdout().lock(), alias, &paint)?;
+            } else {
+                println!("{}", paint(&to_line(alias)));
+            }
         }
     }
 
     Ok(())
 }
 
-fn search_in_field(line: &str, query: &str, field: &str) -> bool {
-    match field.to_lowercase().as_str() {
-        "command" => {
-            if let Some(eq_pos) = line.find('=') {
-                let command_part = &line[eq_pos + 1..];
-                let command = extract_command_from_line(command_part);
-                command.to_lowercase().contains(&query.to_lowercase())
+fn find_matches<'a>(
+    aliases: &'a [Alias],
+    keywords: &[String],
+    match_mode: MatchMode,
+    search_in: Option<&str>,
+    regexes: &[Regex],
+    matcher: Option<&SkimMatcherV2>,
+    config: &SearchConfig,
+) -> Vec<&'a Alias> {
+    if let Some(matcher) = matcher {
+        let mut scored: Vec<(i64, &Alias)> = aliases
+            .iter()
+            .filter_map(|alias| {
+                let scores: Vec<Option<i64>> = keywords
+                    .iter()
+                    .map(|keyword| {
+                        fuzzy_score(matcher, keyword, alias_fields(alias, search_in, config))
+                    })
+                    .collect();
+                match_mode
+                    .combine(scores.iter().map(Option::is_some))
+                    .then(|| (scores.iter().flatten().sum(), alias))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, alias)| alias).collect()
+    } else if !regexes.is_empty() {
+        aliases
+            .iter()
+            .filter(|alias| {
+                let fields = alias_fields(alias, search_in, config);
+                match_mode.combine(
+                    regexes
+                        .iter()
+                        .map(|regex| fields.iter().any(|field| regex.is_match(field))),
+                )
+            })
+            .collect()
+    } else {
+        let fold = |text: &str| {
+            if config.case_sensitive {
+                text.to_string()
             } else {
-                false
+                text.to_lowercase()
             }
+        };
+        let queries: Vec<String> = keywords.iter().map(|keyword| fold(keyword)).collect();
+        aliases
+            .iter()
+            .filter(|alias| {
+                let fields: Vec<String> = alias_fields(alias, search_in, config)
+                    .into_iter()
+                    .map(fold)
+                    .collect();
+                match_mode.combine(
+                    queries
+                        .iter()
+                        .map(|query| fields.iter().any(|field| field.contains(query))),
+                )
+            })
+            .collect()
+    }
+}
+
+pub fn fuzzy_matcher(config: &SearchConfig) -> Option<SkimMatcherV2> {
+    config.fuzzy_matching.then(|| {
+        let matcher = SkimMatcherV2::default();
+        if config.case_sensitive {
+            matcher.respect_case()
+        } else {
+            matcher.ignore_case()
         }
-        "note" => {
-            if let Some(hash_pos) = line.find('#') {
-                let note_part = &line[hash_pos + 1..];
-                if let Some(tags_pos) = note_part.find("#tags:") {
-                    let note = note_part[..tags_pos].trim();
-                    note.to_lowercase().contains(&query.to_lowercase())
-                } else {
-                    note_part
-                        .trim()
-                        .to_lowercase()
-                        .contains(&query.to_lowercase())
-                }
-            } else {
-                false
+    })
+}
+
+pub fn fuzzy_score<'a>(
+    matcher: &SkimMatcherV2,
+    query: &str,
+    fields: impl IntoIterator<Item = &'a str>,
+) -> Option<i64> {
+    fields
+        .into_iter()
+        .filter_map(|field| matcher.fuzzy_match(field, query))
+        .max()
+}
+
+fn alias_fields<'a>(
+    alias: &'a Alias,
+    search_in: Option<&str>,
+    config: &SearchConfig,
+) -> Vec<&'a str> {
+    search_fields(
+        &alias.name,
+        &alias.command,
+        alias.note.as_deref(),
+        &alias.tags,
+        search_in,
+        config,
+    )
+}
+
+/// Picks the alias fields a query looks at, shared by `shorty search --in`
+/// and `field:term` queries in the interactive browser.
+pub(crate) fn search_fields<'a>(
+    name: &'a str,
+    command: &'a str,
+    note: Option<&'a str>,
+    tags: &'a [String],
+    field: Option<&str>,
+    config: &SearchConfig,
+) -> Vec<&'a str> {
+    let tags = tags.iter().map(String::as_str);
+    match field.map(str::to_lowercase).as_deref() {
+        Some("name") => vec![name],
+        Some("cmd" | "command") => vec![command],
+        Some("note") => note.into_iter().collect(),
+        Some("tag" | "tags") => tags.collect(),
+        _ => [name, command]
+            .into_iter()
+            .chain(note.filter(|_| config.search_in_notes))
+            .chain(tags.filter(|_| config.search_in_tags))
+            .collect(),
+    }
+}
+
+enum Highlight<'a> {
+    Pattern(Regex),
+    Fuzzy(&'a SkimMatcherV2, &'a [String]),
+}
+
+impl Highlight<'_> {
+    fn spans(&self, text: &str) -> Vec<Range<usize>> {
+        match self {
+            Highlight::Pattern(regex) => regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect(),
+            Highlight::Fuzzy(matcher, keywords) => {
+                let indices: Vec<usize> = keywords
+                    .iter()
+                    .filter_map(|keyword| matcher.fuzzy_indices(text, keyword))
+                    .flat_map(|(_, indices)| indices)
+                    .collect();
+                text.char_indices()
+                    .enumerate()
+                    .filter(|(i, _)| indices.contains(i))
+                    .map(|(_, (start, c))| start..start + c.len_utf8())
+                    .collect()
             }
         }
-        "tag" => {
-            if let Some(tags_pos) = line.find("#tags:") {
-                let tags_part = &line[tags_pos + 6..];
-                tags_part.to_lowercase().contains(&query.to_lowercase())
-            } else {
-                false
-            }
+    }
+
+    fn apply(&self, text: &str) -> String {
+        let mut painted = String::new();
+        let mut last_end = 0;
+        for span in self.spans(text) {
+            painted.push_str(&text[last_end..span.start]);
+            painted.push_str(&text[span.clone()].black().on_yellow().to_string());
+            last_end = span.end;
         }
-        _ => line.to_lowercase().contains(&query.to_lowercase()),
+        painted.push_str(&text[last_end..]);
+        painted
     }
 }
 
-fn extract_command_from_line(command_part: &str) -> String {
-    let mut command = command_part.trim();
+fn write_alias_context(
+    out: &mut impl Write,
+    alias: &Alias,
+    paint: &dyn Fn(&str) -> String,
+) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(out, "  Name:    {}", paint(&alias.name))?;
+    writeln!(out, "  Command: {}", paint(&alias.command))?;
+    writeln!(
+        out,
+        "  Note:    {}",
+        paint(alias.note.as_deref().unwrap_or("-"))
+    )?;
+    if alias.tags.is_empty() {
+        writeln!(out, "  Tags:    -")
+    } else {
+        writeln!(out, "  Tags:    {}", paint(&alias.tags.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_names_and_their_aliases_select_the_same_fields() {
+        let config = SearchConfig {
+            fuzzy_matching: false,
+            case_sensitive: false,
+            search_in_notes: false,
+            search_in_tags: true,
+        };
+        let tags = vec!["git".to_string()];
+        let fields = |field| search_fields("gs", "git status", Some("show"), &tags, field, &config);
 
-    if (command.starts_with('\'') && command.ends_with('\''))
-        || (command.starts_with('"') && command.ends_with('"'))
-    {
-        command = &command[1..command.len() - 1];
+        assert_eq!(fields(Some("cmd")), fields(Some("command")));
+        assert_eq!(fields(Some("Command")), ["git status"]);
+        assert_eq!(fields(Some("tag")), fields(Some("tags")));
+        assert_eq!(fields(Some("note")), ["show"]);
+        assert_eq!(fields(None), ["gs", "git status", "git"]);
     }
 
-    if let Some(hash_pos) = command.find('#') {
-        command = command[..hash_pos].trim();
+    fn plain_config() -> SearchConfig {
+        SearchConfig {
+            fuzzy_matching: false,
+            case_sensitive: false,
+            search_in_notes: true,
+            search_in_tags: true,
+        }
+    }
+
+    #[test]
+    fn function_block_aliases_match_as_whole_aliases() {
+        let aliases = parse_content(
+            "alias gs='git status'\n\
+             deploy() { # ship it\n    cargo build --release\n    scp target/app host:\n}\n",
+        );
+        let keywords = ["scp".to_string()];
+
+        let matches = find_matches(
+            &aliases,
+            &keywords,
+            MatchMode::Any,
+            None,
+            &[],
+            None,
+            &plain_config(),
+        );
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].name, "deploy");
+        assert_eq!(matches[0].note.as_deref(), Some("ship it"));
+    }
+
+    #[test]
+    fn regex_search_only_looks_at_the_chosen_field() {
+        let aliases = parse_content("alias gs='git status'\nalias gitlog='tig'\n");
+        let keywords = ["^git".to_string()];
+        let regexes = [Regex::new("^git").unwrap()];
+        let names = |search_in| -> Vec<&str> {
+            find_matches(
+                &aliases,
+                &keywords,
+                MatchMode::Any,
+                search_in,
+                &regexes,
+                None,
+                &plain_config(),
+            )
+            .into_iter()
+            .map(|alias| alias.name.as_str())
+            .collect()
+        };
+
+        assert_eq!(names(Some("name")), ["gitlog"]);
+        assert_eq!(names(Some("command")), ["gs"]);
+        assert_eq!(names(None), ["gs", "gitlog"]);
     }
 
-    command.to_string()
+    #[test]
+    fn regex_search_honours_the_configured_note_and_tag_scope() {
+        let aliases = parse_content("alias gs='git status' # daily #tags:vcs\n");
+        let keywords = ["daily|vcs".to_string()];
+        let regexes = [Regex::new("daily|vcs").unwrap()];
+        let matches = |config: &SearchConfig| {
+            find_matches(
+                &aliases,
+                &keywords,
+                MatchMode::Any,
+                None,
+                &regexes,
+                None,
+                config,
+            )
+            .len()
+        };
+
+        assert_eq!(matches(&plain_config()), 1);
+        let unscoped = SearchConfig {
+            search_in_notes: false,
+            search_in_tags: false,
+            ..plain_config()
+        };
+        assert_eq!(matches(&unscoped), 0);
+    }
+
+    #[test]
+    fn context_lists_every_field_of_the_match() {
+        let aliases = parse_content("alias gs='git status' # daily check #tags:git,vcs\n");
+        let mut out = Vec::new();
+
+        write_alias_context(&mut out, &aliases[0], &|text| format!("[{text}]")).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\n  Name:    [gs]\n  Command: [git status]\n  Note:    [daily check]\n  Tags:    [git, vcs]\n"
+        );
+    }
 }
@@ -1,5 +1,8 @@
+use crate::alias::{parse_content, shell_quote};
+use crate::utils::{read_aliases_sources, resolve_output_path};
 use clap::{Command, CommandFactory};
 use clap_complete::{generate, Shell as CompletionShell};
+use std::collections::BTreeMap;
 use std::fs;
 
 #[derive(Debug)]
@@ -117,6 +120,12 @@ fn build_cli() -> Command {
             shell: String,
         },
         Uninstall,
+        Update {
+            #[arg(long, help = "Only check for updates without installing")]
+            check: bool,
+            #[arg(long, help = "Force reinstall current version")]
+            force: bool,
+        },
     }
 
     #[derive(Subcommand)]
@@ -218,7 +227,7 @@ fn build_cli() -> Command {
     Cli::command()
 }
 
-pub fn generate_completion_script(shell: Shell) -> anyhow::Result<()> {
+pub fn generate_completion_script(shell: Shell, output_dir: Option<&str>) -> anyhow::Result<()> {
     let completion_shell = match shell {
         Shell::Bash => CompletionShell::Bash,
         Shell::Zsh => CompletionShell::Zsh,
@@ -232,7 +241,9 @@ pub fn generate_completion_script(shell: Shell) -> anyhow::Result<()> {
     };
 
     let mut cmd = build_cli();
-    let output_path = format!("shorty_completion.{shell_name}");
+    let output_path = resolve_output_path(output_dir, &format!("shorty_completion.{shell_name}"))?
+        .display()
+        .to_string();
 
     let mut file = fs::File::create(&output_path)?;
     generate(completion_shell, &mut cmd, "shorty", &mut file);
@@ -263,3 +274,135 @@ pub fn generate_completion_script(shell: Shell) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+fn alias_descriptions() -> anyhow::Result<BTreeMap<String, String>> {
+    let mut aliases = BTreeMap::new();
+
+    for (_, content) in read_aliases_sources()? {
+        for alias in parse_content(&content) {
+            let description = alias
+                .note
+                .unwrap_or_else(|| alias.command.lines().next().unwrap_or("").to_string());
+            aliases.insert(alias.name, description);
+        }
+    }
+
+    Ok(aliases)
+}
+
+pub(crate) fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn alias_completion_fragment(shell: &Shell, aliases: &BTreeMap<String, String>) -> String {
+    let mut fragment =
+        String::from("# Shorty alias completions (generated by 'shorty completion --aliases')\n");
+
+    match shell {
+        Shell::Bash => {
+            for (name, description) in aliases {
+                fragment.push_str(&format!("# {name}: {description}\n"));
+            }
+            let names: Vec<&str> = aliases.keys().map(String::as_str).collect();
+            fragment.push_str(&format!(
+                "_shorty_alias_names={}\n",
+                shell_quote(&names.join(" "))
+            ));
+            fragment.push_str(
+                "_shorty_complete_aliases() {\n    \
+                 COMPREPLY=($(compgen -c -W \"$_shorty_alias_names\" -- \"${COMP_WORDS[COMP_CWORD]}\"))\n\
+                 }\n\
+                 complete -I -F _shorty_complete_aliases\n",
+            );
+        }
+        Shell::Zsh => {
+            fragment.push_str(
+                "_shorty_aliases() {\n    local -a shorty_aliases\n    shorty_aliases=(\n",
+            );
+            for (name, description) in aliases {
+                let entry = format!("{}:{}", name.replace(':', "\\:"), description);
+                fragment.push_str(&format!("        {}\n", shell_quote(&entry)));
+            }
+            fragment.push_str(
+                "    )\n    \
+                 _describe -t shorty-aliases 'shorty alias' shorty_aliases\n\
+                 }\n\
+                 _shorty_command_names() {\n    \
+                 _shorty_aliases\n    \
+                 _command_names \"$@\"\n\
+                 }\n\
+                 compdef _shorty_command_names -command-\n",
+            );
+        }
+        Shell::Fish => {
+            for (name, description) in aliases {
+                let name = fish_quote(name);
+                fragment.push_str(&format!(
+                    "functions -q {name}; and functions --description {} {name}\n",
+                    fish_quote(description)
+                ));
+            }
+        }
+    }
+
+    fragment
+}
+
+pub fn generate_alias_completions(shell: Shell, output_dir: Option<&str>) -> anyhow::Result<()> {
+    let aliases = alias_descriptions()?;
+    if aliases.is_empty() {
+        println!("No aliases found.");
+        return Ok(());
+    }
+
+    let shell_name = match shell {
+        Shell::Bash => "bash",
+        Shell::Zsh => "zsh",
+        Shell::Fish => "fish",
+    };
+
+    let output_path =
+        resolve_output_path(output_dir, &format!("shorty_alias_completion.{shell_name}"))?;
+    fs::write(&output_path, alias_completion_fragment(&shell, &aliases))?;
+
+    println!(
+        "Generated {shell_name} completions for {} alias(es): {}",
+        aliases.len(),
+        output_path.display()
+    );
+    match shell {
+        Shell::Fish => {
+            println!("Source it from ~/.config/fish/config.fish after your aliases are loaded:")
+        }
+        _ => println!("Source it from your shell rc file after your aliases are loaded:"),
+    }
+    println!("  source {}", output_path.display());
+    println!("Re-run this command after adding or removing aliases.");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alias_fragment_names_every_alias() {
+        let aliases = BTreeMap::from([
+            ("gs".to_string(), "git status".to_string()),
+            ("ll".to_string(), "list files".to_string()),
+        ]);
+
+        let bash = alias_completion_fragment(&Shell::Bash, &aliases);
+        assert!(bash.contains("_shorty_alias_names='gs ll'"), "{bash}");
+
+        let zsh = alias_completion_fragment(&Shell::Zsh, &aliases);
+        assert!(zsh.contains("'gs:git status'"), "{zsh}");
+
+        let fish = alias_completion_fragment(&Shell::Fish, &aliases);
+        assert!(
+            fish.contains("functions --description 'git status' 'gs'"),
+            "{fish}"
+        );
+    }
+}
@@ -1,7 +1,26 @@
 use chrono::{DateTime, Local};
-use std::{collections::HashMap, fs, path::Path};
-
-use crate::utils::get_aliases_path;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::alias::parse_content;
+use crate::commands::validate::command_exists;
+use crate::utils::{
+    get_aliases_path, read_aliases_file, read_aliases_sources, read_usage, OutputFormat,
+};
 
 #[derive(Debug)]
 struct AliasStats {
@@ -15,26 +34,156 @@ struct AliasStats {
     longest_command: String,
     shortest_command: String,
     most_common_commands: Vec<(String, usize)>,
+    most_used: Vec<(String, u64)>,
+    never_used: usize,
+    missing_commands: Vec<(String, String)>,
+    redundant_groups: Vec<(String, Vec<String>)>,
+    length_buckets: Vec<(&'static str, usize)>,
 }
 
-pub fn show_stats() -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
+const LENGTH_BUCKETS: &[(&str, usize)] = &[
+    ("0-20", 20),
+    ("21-50", 50),
+    ("51-100", 100),
+    ("101+", usize::MAX),
+];
+
+pub fn show_stats(
+    watch: bool,
+    interval_secs: u64,
+    format: OutputFormat,
+    by_length: bool,
+) -> anyhow::Result<()> {
+    if watch {
+        return watch_stats(interval_secs.max(1));
+    }
 
-    if !aliases_path.exists() {
-        println!("No aliases file found. Create some aliases first!");
+    if by_length {
+        let stats = match collect_stats()? {
+            Some((stats, _)) => stats,
+            None => analyze_aliases("")?,
+        };
+        match format {
+            OutputFormat::Text => display_length_histogram(&mut io::stdout(), &stats)?,
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&length_buckets_json(&stats))?
+            ),
+        }
         return Ok(());
     }
 
-    let stats = analyze_aliases(&aliases_path)?;
+    match format {
+        OutputFormat::Text => print!("{}", render_report()?),
+        OutputFormat::Json => {
+            let (stats, file_stats) = match collect_stats()? {
+                Some((stats, file_stats)) => (stats, Some(file_stats)),
+                None => (analyze_aliases("")?, None),
+            };
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&stats_json(&stats, file_stats.as_ref()))?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn collect_stats() -> anyhow::Result<Option<(AliasStats, FileStats)>> {
+    let aliases_path = get_aliases_path()?;
+
+    if !aliases_path.exists() {
+        return Ok(None);
+    }
+
+    let content = read_aliases_sources()?
+        .into_iter()
+        .map(|(_, content)| content)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let stats = analyze_aliases(&content)?;
     let file_stats = get_file_stats(&aliases_path)?;
 
-    display_stats(&stats, &file_stats)?;
+    Ok(Some((stats, file_stats)))
+}
 
-    Ok(())
+fn render_report() -> anyhow::Result<String> {
+    let Some((stats, file_stats)) = collect_stats()? else {
+        return Ok("No aliases file found. Create some aliases first!\n".to_string());
+    };
+
+    let mut report = Vec::new();
+    display_stats(&mut report, &stats, &file_stats)?;
+
+    Ok(String::from_utf8(report)?)
+}
+
+fn watch_stats(interval_secs: u64) -> anyhow::Result<()> {
+    let mut stdout = io::stdout();
+    enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+
+    let result = watch_loop(
+        &mut stdout,
+        Duration::from_secs(interval_secs),
+        quit_requested,
+    );
+
+    execute!(stdout, Show, LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+
+    result
 }
 
-fn analyze_aliases(aliases_path: &Path) -> anyhow::Result<AliasStats> {
-    let content = fs::read_to_string(aliases_path)?;
+fn watch_loop(
+    out: &mut impl Write,
+    interval: Duration,
+    mut quit: impl FnMut(Duration) -> anyhow::Result<bool>,
+) -> anyhow::Result<()> {
+    loop {
+        let report = render_report()?;
+        execute!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+        write!(out, "{}", report.replace('\n', "\r\n"))?;
+        write!(
+            out,
+            "\r\nRefreshing every {}s ({}). Press q or Ctrl+C to exit.",
+            interval.as_secs(),
+            Local::now().format("%H:%M:%S")
+        )?;
+        out.flush()?;
+
+        if quit(interval)? {
+            return Ok(());
+        }
+    }
+}
+
+fn quit_requested(timeout: Duration) -> anyhow::Result<bool> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || !event::poll(remaining)? {
+            return Ok(false);
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(true)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn analyze_aliases(content: &str) -> anyhow::Result<AliasStats> {
     let mut stats = AliasStats {
         total_aliases: 0,
         aliases_with_notes: 0,
@@ -46,51 +195,72 @@ fn analyze_aliases(aliases_path: &Path) -> anyhow::Result<AliasStats> {
         longest_command: String::new(),
         shortest_command: String::new(),
         most_common_commands: Vec::new(),
+        most_used: Vec::new(),
+        never_used: 0,
+        missing_commands: Vec::new(),
+        redundant_groups: Vec::new(),
+        length_buckets: LENGTH_BUCKETS
+            .iter()
+            .map(|(label, _)| (*label, 0))
+            .collect(),
     };
 
     let mut command_lengths = Vec::new();
     let mut command_frequency = HashMap::new();
     let mut all_tags = std::collections::HashSet::new();
-
-    for line in content.lines() {
-        let line = line.trim();
-
-        if line.is_empty() || line.starts_with('#') {
-            continue;
+    let usage = read_usage()?;
+    let mut used = Vec::new();
+    let mut seen_names = std::collections::HashSet::new();
+    let mut first_words: Vec<(String, String)> = Vec::new();
+    let mut by_command: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for alias in parse_content(content) {
+        stats.total_aliases += 1;
+        if seen_names.insert(alias.name.clone()) {
+            match usage.aliases.get(&alias.name) {
+                Some(entry) if entry.count > 0 => used.push((alias.name.clone(), entry.count)),
+                _ => stats.never_used += 1,
+            }
         }
+        let command = alias.command;
 
-        if line.starts_with("alias ") {
-            stats.total_aliases += 1;
+        command_lengths.push(command.len());
+        let length = command.chars().count();
+        if let Some(bucket) = LENGTH_BUCKETS.iter().position(|(_, max)| length <= *max) {
+            stats.length_buckets[bucket].1 += 1;
+        }
 
-            if let Some((_, command, note, tags)) = parse_alias_line(line) {
-                command_lengths.push(command.len());
+        if stats.longest_command.len() < command.len() {
+            stats.longest_command = command.clone();
+        }
 
-                if stats.longest_command.len() < command.len() {
-                    stats.longest_command = command.clone();
-                }
+        if stats.shortest_command.is_empty() || stats.shortest_command.len() > command.len() {
+            stats.shortest_command = command.clone();
+        }
 
-                if stats.shortest_command.is_empty() || stats.shortest_command.len() > command.len()
-                {
-                    stats.shortest_command = command.clone();
-                }
+        let command_type = classify_command(&command);
+        *stats.command_types.entry(command_type).or_insert(0) += 1;
 
-                let command_type = classify_command(&command);
-                *stats.command_types.entry(command_type).or_insert(0) += 1;
+        let first_word = command.split_whitespace().next().unwrap_or(&command);
+        *command_frequency.entry(first_word.to_string()).or_insert(0) += 1;
 
-                let first_word = command.split_whitespace().next().unwrap_or(&command);
-                *command_frequency.entry(first_word.to_string()).or_insert(0) += 1;
+        if !first_word.is_empty() && !first_word.contains('=') {
+            first_words.push((alias.name.clone(), first_word.to_string()));
+        }
+        let names = by_command.entry(command.clone()).or_default();
+        if !names.contains(&alias.name) {
+            names.push(alias.name.clone());
+        }
 
-                if note.is_some() {
-                    stats.aliases_with_notes += 1;
-                }
+        if alias.note.is_some() {
+            stats.aliases_with_notes += 1;
+        }
 
-                if !tags.is_empty() {
-                    stats.aliases_with_tags += 1;
-                    for tag in &tags {
-                        all_tags.insert(tag.clone());
-                        *stats.tag_frequency.entry(tag.clone()).or_insert(0) += 1;
-                    }
-                }
+        if !alias.tags.is_empty() {
+            stats.aliases_with_tags += 1;
+            for tag in &alias.tags {
+                all_tags.insert(tag.clone());
+                *stats.tag_frequency.entry(tag.clone()).or_insert(0) += 1;
             }
         }
     }
@@ -104,12 +274,112 @@ fn analyze_aliases(aliases_path: &Path) -> anyhow::Result<AliasStats> {
     stats.unique_tags = all_tags.len();
 
     let mut sorted_commands: Vec<_> = command_frequency.into_iter().collect();
-    sorted_commands.sort_by(|a, b| b.1.cmp(&a.1));
+    sorted_commands.sort_by_key(|b| std::cmp::Reverse(b.1));
     stats.most_common_commands = sorted_commands.into_iter().take(5).collect();
 
+    stats.missing_commands = first_words
+        .into_iter()
+        .filter(|(_, word)| !seen_names.contains(word) && !command_exists(word))
+        .collect();
+    stats.redundant_groups = by_command
+        .into_iter()
+        .filter(|(command, names)| !command.is_empty() && names.len() > 1)
+        .collect();
+
+    used.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    stats.most_used = used.into_iter().take(5).collect();
+
     Ok(stats)
 }
 
+fn stats_json(stats: &AliasStats, file_stats: Option<&FileStats>) -> serde_json::Value {
+    let tag_frequency: BTreeMap<_, _> = stats.tag_frequency.iter().collect();
+    let command_types: BTreeMap<_, _> = stats
+        .command_types
+        .iter()
+        .map(|(cmd_type, count)| {
+            (
+                cmd_type,
+                serde_json::json!({
+                    "count": count,
+                    "percentage": percentage(*count, stats.total_aliases),
+                }),
+            )
+        })
+        .collect();
+
+    serde_json::json!({
+        "total_aliases": stats.total_aliases,
+        "aliases_with_notes": stats.aliases_with_notes,
+        "aliases_with_notes_percentage": percentage(stats.aliases_with_notes, stats.total_aliases),
+        "aliases_with_tags": stats.aliases_with_tags,
+        "aliases_with_tags_percentage": percentage(stats.aliases_with_tags, stats.total_aliases),
+        "unique_tags": stats.unique_tags,
+        "tag_frequency": tag_frequency,
+        "command_types": command_types,
+        "avg_command_length": stats.avg_command_length,
+        "longest_command": stats.longest_command,
+        "shortest_command": stats.shortest_command,
+        "most_common_commands": stats
+            .most_common_commands
+            .iter()
+            .map(|(command, count)| serde_json::json!({ "command": command, "count": count }))
+            .collect::<Vec<_>>(),
+        "most_used": stats
+            .most_used
+            .iter()
+            .map(|(name, count)| serde_json::json!({ "name": name, "count": count }))
+            .collect::<Vec<_>>(),
+        "never_used": stats.never_used,
+        "missing_commands": stats
+            .missing_commands
+            .iter()
+            .map(|(name, command)| serde_json::json!({ "alias": name, "command": command }))
+            .collect::<Vec<_>>(),
+        "redundant_groups": stats
+            .redundant_groups
+            .iter()
+            .map(|(command, names)| serde_json::json!({ "command": command, "aliases": names }))
+            .collect::<Vec<_>>(),
+        "length_buckets": length_buckets_json(stats),
+        "file": file_stats.map(|file_stats| serde_json::json!({
+            "size_bytes": file_stats.file_size,
+            "line_count": file_stats.line_count,
+            "last_modified": file_stats.last_modified.to_rfc3339(),
+        })),
+    })
+}
+
+fn length_buckets_json(stats: &AliasStats) -> serde_json::Value {
+    stats
+        .length_buckets
+        .iter()
+        .map(|(range, count)| serde_json::json!({ "range": range, "count": count }))
+        .collect()
+}
+
+fn display_length_histogram(out: &mut impl Write, stats: &AliasStats) -> anyhow::Result<()> {
+    const BAR_WIDTH: usize = 40;
+
+    writeln!(out, "Command Length Distribution:")?;
+    let max = stats
+        .length_buckets
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0);
+    for (range, count) in &stats.length_buckets {
+        let bar = if max == 0 {
+            0
+        } else {
+            (count * BAR_WIDTH).div_ceil(max)
+        };
+        writeln!(out, "  {range:<7} {:<BAR_WIDTH$} {count}", "█".repeat(bar))?;
+    }
+
+    Ok(())
+}
+
 fn get_file_stats(aliases_path: &Path) -> anyhow::Result<FileStats> {
     let metadata = fs::metadata(aliases_path)?;
     let modified = metadata.modified()?;
@@ -118,110 +388,169 @@ fn get_file_stats(aliases_path: &Path) -> anyhow::Result<FileStats> {
     Ok(FileStats {
         file_size: metadata.len(),
         last_modified: datetime,
-        line_count: fs::read_to_string(aliases_path)?.lines().count(),
+        line_count: read_aliases_file(aliases_path)?.lines().count(),
     })
 }
 
-fn display_stats(stats: &AliasStats, file_stats: &FileStats) -> anyhow::Result<()> {
-    println!("Shorty Statistics Report");
-    println!("═══════════════════════════\n");
-
-    println!("Overview:");
-    println!("  Total aliases: {}", stats.total_aliases);
-    println!(
+fn display_stats(
+    out: &mut impl Write,
+    stats: &AliasStats,
+    file_stats: &FileStats,
+) -> anyhow::Result<()> {
+    writeln!(out, "Shorty Statistics Report")?;
+    writeln!(out, "═══════════════════════════\n")?;
+
+    writeln!(out, "Overview:")?;
+    writeln!(out, "  Total aliases: {}", stats.total_aliases)?;
+    writeln!(
+        out,
         "  Aliases with notes: {} ({:.1}%)",
         stats.aliases_with_notes,
         percentage(stats.aliases_with_notes, stats.total_aliases)
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "  Aliases with tags: {} ({:.1}%)",
         stats.aliases_with_tags,
         percentage(stats.aliases_with_tags, stats.total_aliases)
-    );
-    println!("  Unique tags: {}", stats.unique_tags);
+    )?;
+    writeln!(out, "  Unique tags: {}", stats.unique_tags)?;
 
-    println!("\nCommand Analysis:");
-    println!(
+    writeln!(out, "\nCommand Analysis:")?;
+    writeln!(
+        out,
         "  Average command length: {:.1} characters",
         stats.avg_command_length
-    );
+    )?;
     if !stats.longest_command.is_empty() {
-        println!(
+        writeln!(
+            out,
             "  Longest command: {} ({} chars)",
             truncate(&stats.longest_command, 50),
             stats.longest_command.len()
-        );
+        )?;
     }
     if !stats.shortest_command.is_empty() {
-        println!(
+        writeln!(
+            out,
             "  Shortest command: {} ({} chars)",
             truncate(&stats.shortest_command, 50),
             stats.shortest_command.len()
-        );
+        )?;
     }
 
     if !stats.command_types.is_empty() {
-        println!("\nCommand Types:");
+        writeln!(out, "\nCommand Types:")?;
         let mut sorted_types: Vec<_> = stats.command_types.iter().collect();
         sorted_types.sort_by(|a, b| b.1.cmp(a.1));
 
         for (cmd_type, count) in sorted_types.iter().take(5) {
-            println!(
+            writeln!(
+                out,
                 "  {}: {} ({:.1}%)",
                 cmd_type,
                 count,
                 percentage(**count, stats.total_aliases)
-            );
+            )?;
         }
     }
 
     if !stats.most_common_commands.is_empty() {
-        println!("\nMost Common Commands:");
+        writeln!(out, "\nMost Common Commands:")?;
         for (i, (command, count)) in stats.most_common_commands.iter().enumerate() {
-            println!("  {}. {} ({}x)", i + 1, command, count);
+            writeln!(out, "  {}. {} ({}x)", i + 1, command, count)?;
         }
     }
 
+    if !stats.most_used.is_empty() {
+        writeln!(out, "\nMost Used Aliases:")?;
+        for (i, (name, count)) in stats.most_used.iter().enumerate() {
+            writeln!(out, "  {}. {} ({}x)", i + 1, name, count)?;
+        }
+        writeln!(out, "  Never used: {}", stats.never_used)?;
+    }
+
     if !stats.tag_frequency.is_empty() {
-        println!("\nPopular Tags:");
+        writeln!(out, "\nPopular Tags:")?;
         let mut sorted_tags: Vec<_> = stats.tag_frequency.iter().collect();
         sorted_tags.sort_by(|a, b| b.1.cmp(a.1));
 
         for (tag, count) in sorted_tags.iter().take(5) {
-            println!("  #{tag}: {count}x");
+            writeln!(out, "  #{tag}: {count}x")?;
+        }
+    }
+
+    if !stats.missing_commands.is_empty() {
+        writeln!(out, "\nMissing Commands:")?;
+        for (name, command) in &stats.missing_commands {
+            writeln!(out, "  {name}: '{command}' not found on PATH")?;
         }
     }
 
-    println!("\nFile Information:");
-    println!("  File size: {}", format_file_size(file_stats.file_size));
-    println!("  Total lines: {}", file_stats.line_count);
-    println!(
+    if !stats.redundant_groups.is_empty() {
+        writeln!(out, "\nRedundant Aliases:")?;
+        for (command, names) in &stats.redundant_groups {
+            writeln!(out, "  {} -> {}", names.join(", "), truncate(command, 50))?;
+        }
+    }
+
+    writeln!(out, "\nFile Information:")?;
+    writeln!(
+        out,
+        "  File size: {}",
+        format_file_size(file_stats.file_size)
+    )?;
+    writeln!(out, "  Total lines: {}", file_stats.line_count)?;
+    writeln!(
+        out,
         "  Last modified: {}",
         file_stats.last_modified.format("%Y-%m-%d %H:%M:%S")
-    );
+    )?;
 
-    println!("\nRecommendations:");
+    writeln!(out, "\nRecommendations:")?;
 
     if stats.aliases_with_notes < stats.total_aliases / 2 {
-        println!("  • Consider adding notes to more aliases for better organization");
+        writeln!(
+            out,
+            "  • Consider adding notes to more aliases for better organization"
+        )?;
     }
 
     if stats.aliases_with_tags < stats.total_aliases / 3 {
-        println!("  • Try using tags to categorize your aliases");
+        writeln!(out, "  • Try using tags to categorize your aliases")?;
     }
 
     if stats.avg_command_length > 100.0 {
-        println!("  • Some commands are quite long - consider breaking them down");
+        writeln!(
+            out,
+            "  • Some commands are quite long - consider breaking them down"
+        )?;
     }
 
     if stats.total_aliases > 50 && stats.unique_tags < 5 {
-        println!(
+        writeln!(
+            out,
             "  • With {} aliases, more tags could help with organization",
             stats.total_aliases
-        );
+        )?;
     }
 
-    println!("\nUse 'shorty validate' to check for potential issues");
+    if !stats.missing_commands.is_empty() || !stats.redundant_groups.is_empty() {
+        writeln!(
+            out,
+            "  • Remove aliases whose commands are missing and merge redundant ones"
+        )?;
+    }
+
+    if !stats.most_used.is_empty() && stats.never_used > 0 {
+        writeln!(
+            out,
+            "  • {} aliases have never been used - 'shorty list --sort used' shows them last",
+            stats.never_used
+        )?;
+    }
+
+    writeln!(out, "\nUse 'shorty validate' to check for potential issues")?;
 
     Ok(())
 }
@@ -233,60 +562,6 @@ struct FileStats {
     line_count: usize,
 }
 
-fn parse_alias_line(line: &str) -> Option<(String, String, Option<String>, Vec<String>)> {
-    if !line.starts_with("alias ") {
-        return None;
-    }
-
-    let eq_pos = line.find('=')?;
-    let name = line[6..eq_pos].trim().to_string();
-    let rest = &line[eq_pos + 1..];
-
-    let mut command = String::new();
-    let mut remaining = "";
-
-    let rest = rest.trim();
-    if let Some(stripped) = rest.strip_prefix('\'') {
-        if let Some(end_quote) = stripped.find('\'') {
-            command = stripped[..end_quote].to_string();
-            remaining = &rest[end_quote + 2..];
-        }
-    } else if let Some(stripped) = rest.strip_prefix('"') {
-        if let Some(end_quote) = stripped.find('"') {
-            command = stripped[..end_quote].to_string();
-            remaining = &rest[end_quote + 2..];
-        }
-    } else if let Some(hash_pos) = rest.find('#') {
-        command = rest[..hash_pos].trim().to_string();
-        remaining = &rest[hash_pos..];
-    } else {
-        command = rest.to_string();
-    }
-
-    let mut note = None;
-    let mut tags = Vec::new();
-
-    if let Some(tags_pos) = remaining.find("#tags:") {
-        let tags_part = &remaining[tags_pos + 6..];
-        tags = tags_part.split(',').map(|s| s.trim().to_string()).collect();
-
-        let note_part = remaining[..tags_pos].trim();
-        if let Some(stripped) = note_part.strip_prefix('#') {
-            let note_text = stripped.trim();
-            if !note_text.is_empty() {
-                note = Some(note_text.to_string());
-            }
-        }
-    } else if remaining.trim().starts_with('#') {
-        let note_text = remaining.trim()[1..].trim();
-        if !note_text.is_empty() {
-            note = Some(note_text.to_string());
-        }
-    }
-
-    Some((name, command, note, tags))
-}
-
 fn classify_command(command: &str) -> String {
     let first_word = command.split_whitespace().next().unwrap_or(command);
 
@@ -343,3 +618,44 @@ fn format_file_size(size: u64) -> String {
         format!("{:.1} {}", size, UNITS[unit_index])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+
+    #[test]
+    fn watch_loop_renders_once_and_stops_when_quit_is_requested() {
+        let home = FakeHome::new("stats-watch");
+        home.write("aliases", "alias gs='git status'\n");
+
+        let mut out = Vec::new();
+        let mut waits = Vec::new();
+        watch_loop(&mut out, Duration::from_secs(3), |timeout| {
+            waits.push(timeout);
+            Ok(true)
+        })
+        .unwrap();
+
+        assert_eq!(waits, [Duration::from_secs(3)]);
+        let screen = String::from_utf8(out).unwrap();
+        assert!(screen.contains("Refreshing every 3s"), "{screen}");
+        assert!(screen.contains("\r\n"));
+    }
+
+    #[test]
+    fn command_lengths_fall_into_inclusive_buckets() {
+        let _home = FakeHome::new("stats-buckets");
+        let content: String = [5, 20, 21, 50, 51, 100, 101, 300]
+            .iter()
+            .enumerate()
+            .map(|(i, length)| format!("alias a{i}='{}'\n", "x".repeat(*length)))
+            .collect();
+
+        let stats = analyze_aliases(&content).unwrap();
+        assert_eq!(
+            stats.length_buckets,
+            [("0-20", 2), ("21-50", 2), ("51-100", 2), ("101+", 2)]
+        );
+    }
+}
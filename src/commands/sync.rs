@@ -1,8 +1,23 @@
-use crate::utils::get_aliases_path;
+use crate::alias::{parse_content, Alias};
+use crate::commands::backup::{command_map, print_alias_diff};
+use crate::commands::shell_integration::fish_quote;
+use crate::utils::{
+    expand_home, get_aliases_path, read_aliases_file, resolve_output_path, write_aliases_atomic,
+    OutputFormat,
+};
+use anyhow::Context;
+use arboard::Clipboard;
 use chrono::Local;
+use image::Luma;
+use qrcode::render::unicode;
+use qrcode::types::QrError;
+use qrcode::{EcLevel, QrCode};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{
+    collections::BTreeMap,
     fs,
+    io::{self, Write},
     path::{Path, PathBuf},
     process::Command,
 };
@@ -26,6 +41,96 @@ struct SyncMetadata {
     checksum: String,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum ShareFormat {
+    Bash,
+    Zsh,
+    Fish,
+    Nu,
+    PowerShell,
+}
+
+impl std::str::FromStr for ShareFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bash" => Ok(ShareFormat::Bash),
+            "zsh" => Ok(ShareFormat::Zsh),
+            "fish" => Ok(ShareFormat::Fish),
+            "nu" | "nushell" => Ok(ShareFormat::Nu),
+            "powershell" | "pwsh" => Ok(ShareFormat::PowerShell),
+            _ => anyhow::bail!(
+                "Unsupported share format: {}. Supported: bash, zsh, fish, nu, powershell",
+                s
+            ),
+        }
+    }
+}
+
+impl ShareFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ShareFormat::Bash => "sh",
+            ShareFormat::Zsh => "zsh",
+            ShareFormat::Fish => "fish",
+            ShareFormat::Nu => "nu",
+            ShareFormat::PowerShell => "ps1",
+        }
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            ShareFormat::Bash => "#!/bin/bash\n",
+            ShareFormat::Zsh => "#!/bin/zsh\n",
+            ShareFormat::Fish => "#!/usr/bin/env fish\n",
+            ShareFormat::Nu => "#!/usr/bin/env nu\n",
+            ShareFormat::PowerShell => "",
+        }
+    }
+
+    fn definition(self, alias: &Alias) -> anyhow::Result<String> {
+        let name = &alias.name;
+        let command = &alias.command;
+        let multiline = command.contains('\n');
+
+        Ok(match self {
+            ShareFormat::Bash | ShareFormat::Zsh => crate::alias::to_line(alias),
+            ShareFormat::Fish if multiline => format!("function {name}\n{}\nend", indent(command)),
+            ShareFormat::Fish => format!("alias {name} {}", fish_quote(command)),
+            ShareFormat::Nu if multiline => anyhow::bail!(
+                "Alias '{}' spans multiple lines and cannot be shared in nu format",
+                name
+            ),
+            ShareFormat::Nu => format!("alias {name} = {command}"),
+            ShareFormat::PowerShell if multiline => {
+                format!("function {name} {{\n{}\n}}", indent(command))
+            }
+            ShareFormat::PowerShell => format!("function {name} {{ {command} @args }}"),
+        })
+    }
+
+    fn render(self, alias: &Alias) -> anyhow::Result<String> {
+        let mut content = format!("{}# Shared alias from Shorty\n", self.header());
+        if !matches!(self, ShareFormat::Bash | ShareFormat::Zsh) {
+            if let Some(note) = &alias.note {
+                content.push_str(&format!("# {note}\n"));
+            }
+        }
+        content.push_str(&self.definition(alias)?);
+        content.push('\n');
+        Ok(content)
+    }
+}
+
+fn indent(command: &str) -> String {
+    command
+        .lines()
+        .map(|line| format!("    {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 pub fn init_sync(remote_url: Option<&str>, branch: Option<&str>) -> anyhow::Result<()> {
     let sync_dir = get_sync_dir()?;
 
@@ -47,6 +152,20 @@ pub fn init_sync(remote_url: Option<&str>, branch: Option<&str>) -> anyhow::Resu
         );
     }
 
+    let branch = branch.unwrap_or("main");
+    let output = Command::new("git")
+        .args(["symbolic-ref", "HEAD", &format!("refs/heads/{branch}")])
+        .current_dir(&sync_dir)
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to set branch '{}': {}",
+            branch,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     if let Some(url) = remote_url {
         let output = Command::new("git")
             .args(["remote", "add", "origin", url])
@@ -63,7 +182,7 @@ pub fn init_sync(remote_url: Option<&str>, branch: Option<&str>) -> anyhow::Resu
 
     let config = SyncConfig {
         remote_url: remote_url.unwrap_or("").to_string(),
-        branch: branch.unwrap_or("main").to_string(),
+        branch: branch.to_string(),
         last_sync: "never".to_string(),
         auto_sync: false,
         sync_interval: 60,
@@ -73,6 +192,7 @@ pub fn init_sync(remote_url: Option<&str>, branch: Option<&str>) -> anyhow::Resu
 
     copy_aliases_to_sync_dir(&sync_dir)?;
     create_initial_commit(&sync_dir)?;
+    ensure_branch(&sync_dir, branch)?;
 
     println!("Sync initialized successfully");
     println!("Sync directory: {}", sync_dir.display());
@@ -87,7 +207,169 @@ pub fn init_sync(remote_url: Option<&str>, branch: Option<&str>) -> anyhow::Resu
     Ok(())
 }
 
-pub fn push_sync() -> anyhow::Result<()> {
+pub fn clone_sync(url: &str, branch: Option<&str>, force: bool) -> anyhow::Result<()> {
+    let sync_dir = get_sync_dir()?;
+
+    if sync_dir.exists() && !force {
+        anyhow::bail!(
+            "Sync directory already exists: {}. Use --force to replace it",
+            sync_dir.display()
+        );
+    }
+
+    // Clone next to the sync directory so an existing one survives a failed clone.
+    let clone_dir = sync_dir.with_file_name("sync.clone-tmp");
+    if clone_dir.exists() {
+        fs::remove_dir_all(&clone_dir)?;
+    }
+
+    let mut args = vec!["clone"];
+    if let Some(branch) = branch {
+        args.extend(["--branch", branch]);
+    }
+    let clone_dir_arg = clone_dir.to_string_lossy();
+    args.extend([url, &clone_dir_arg]);
+
+    let output = Command::new("git").args(&args).output()?;
+
+    if !output.status.success() {
+        fs::remove_dir_all(&clone_dir).ok();
+        anyhow::bail!(
+            "Failed to clone {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    if sync_dir.exists() {
+        fs::remove_dir_all(&sync_dir)?;
+    }
+    fs::rename(&clone_dir, &sync_dir)?;
+
+    let mut is_empty = !Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .current_dir(&sync_dir)
+        .output()?
+        .status
+        .success();
+
+    if is_empty && branch.is_none() {
+        if let Some(remote_branch) = default_remote_branch(&sync_dir)? {
+            let output = Command::new("git")
+                .args([
+                    "checkout",
+                    "-q",
+                    "-b",
+                    &remote_branch,
+                    "--track",
+                    &format!("origin/{remote_branch}"),
+                ])
+                .current_dir(&sync_dir)
+                .output()?;
+
+            if !output.status.success() {
+                anyhow::bail!(
+                    "Failed to check out branch '{}': {}",
+                    remote_branch,
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+            is_empty = false;
+        }
+    }
+
+    let branch = match branch {
+        Some(branch) => branch.to_string(),
+        None if is_empty => "main".to_string(),
+        None => {
+            let output = Command::new("git")
+                .args(["symbolic-ref", "--short", "HEAD"])
+                .current_dir(&sync_dir)
+                .output()?;
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+    };
+
+    let mut config = SyncConfig {
+        remote_url: url.to_string(),
+        branch: branch.clone(),
+        last_sync: "never".to_string(),
+        auto_sync: false,
+        sync_interval: 60,
+    };
+
+    if is_empty {
+        let output = Command::new("git")
+            .args(["symbolic-ref", "HEAD", &format!("refs/heads/{branch}")])
+            .current_dir(&sync_dir)
+            .output()?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to set branch '{}': {}",
+                branch,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        save_sync_config(&config)?;
+        copy_aliases_to_sync_dir(&sync_dir)?;
+        create_initial_commit(&sync_dir)?;
+
+        println!("Cloned empty repository: {url}");
+        println!("Run 'shorty sync push' to upload your aliases");
+        return Ok(());
+    }
+
+    ensure_branch(&sync_dir, &branch)?;
+
+    if sync_dir.join("aliases").exists() {
+        let aliases_path = get_aliases_path()?;
+        if aliases_path.exists() {
+            println!(
+                "Existing aliases backed up to: {}",
+                aliases_path.with_extension("backup").display()
+            );
+        }
+        copy_aliases_from_sync_dir(&sync_dir)?;
+        config.last_sync = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        println!("Aliases downloaded from {url}");
+    } else {
+        println!("Warning: no aliases file found in {url}; local aliases were left unchanged");
+    }
+
+    save_sync_config(&config)?;
+
+    println!("Sync directory: {}", sync_dir.display());
+    println!("Branch: {branch}");
+
+    Ok(())
+}
+
+fn default_remote_branch(sync_dir: &Path) -> anyhow::Result<Option<String>> {
+    let output = Command::new("git")
+        .args([
+            "for-each-ref",
+            "--format=%(refname:lstrip=3)",
+            "refs/remotes/origin",
+        ])
+        .current_dir(sync_dir)
+        .output()?;
+
+    let branches: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|branch| *branch != "HEAD")
+        .map(str::to_string)
+        .collect();
+
+    Ok(branches
+        .iter()
+        .find(|branch| *branch == "main")
+        .or_else(|| branches.first())
+        .cloned())
+}
+
+pub fn push_sync(force: bool, yes: bool) -> anyhow::Result<()> {
     let sync_dir = get_sync_dir()?;
     let config = load_sync_config()?;
 
@@ -95,6 +377,21 @@ pub fn push_sync() -> anyhow::Result<()> {
         anyhow::bail!("No remote configured. Add one with 'shorty sync remote add <url>'");
     }
 
+    if force && !yes {
+        print!(
+            "This will overwrite branch '{}' on {} with your local aliases. Continue? (y/n): ",
+            config.branch, config.remote_url
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Operation aborted.");
+            return Ok(());
+        }
+    }
+
+    ensure_branch(&sync_dir, &config.branch)?;
     copy_aliases_to_sync_dir(&sync_dir)?;
 
     let status_output = Command::new("git")
@@ -102,51 +399,58 @@ pub fn push_sync() -> anyhow::Result<()> {
         .current_dir(&sync_dir)
         .output()?;
 
-    if status_output.stdout.is_empty() {
+    let change_count = String::from_utf8_lossy(&status_output.stdout)
+        .lines()
+        .count();
+    if change_count == 0 && !force {
         println!("No changes to sync");
         return Ok(());
     }
 
-    let changes = String::from_utf8_lossy(&status_output.stdout);
-    let change_count = changes.lines().count();
+    if change_count > 0 {
+        let output = Command::new("git")
+            .args(["add", "."])
+            .current_dir(&sync_dir)
+            .output()?;
 
-    let output = Command::new("git")
-        .args(["add", "."])
-        .current_dir(&sync_dir)
-        .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to stage changes: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
 
-    if !output.status.success() {
-        anyhow::bail!(
-            "Failed to stage changes: {}",
-            String::from_utf8_lossy(&output.stderr)
+        let commit_message = format!(
+            "Update aliases - {} changes from {}",
+            change_count,
+            whoami::fallible::hostname().unwrap_or_else(|_| "unknown".to_string())
         );
-    }
-
-    let commit_message = format!(
-        "Update aliases - {} changes from {}",
-        change_count,
-        whoami::fallible::hostname().unwrap_or_else(|_| "unknown".to_string())
-    );
 
-    let output = Command::new("git")
-        .args(["commit", "-m", &commit_message])
-        .current_dir(&sync_dir)
-        .output()?;
+        let output = Command::new("git")
+            .args(["commit", "-m", &commit_message])
+            .current_dir(&sync_dir)
+            .output()?;
 
-    if !output.status.success() {
-        anyhow::bail!(
-            "Failed to commit changes: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to commit changes: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
     }
 
     let output = Command::new("git")
-        .args(["push", "origin", &config.branch])
+        .args(push_args(&config.branch, force))
         .current_dir(&sync_dir)
         .output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if force && stderr.contains("rejected") {
+            println!("Force push rejected: the remote has changes you have not reviewed yet.");
+            println!("Run 'shorty sync diff --remote' to fetch and review them, then push again");
+            return Ok(());
+        }
         if stderr.contains("rejected") {
             println!("Push rejected. There might be remote changes.");
             println!("Run 'shorty sync pull' first to merge remote changes");
@@ -159,12 +463,28 @@ pub fn push_sync() -> anyhow::Result<()> {
     new_config.last_sync = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
     save_sync_config(&new_config)?;
 
-    println!("Successfully pushed {change_count} changes");
+    if force {
+        println!(
+            "Force-pushed local aliases to branch '{}'",
+            new_config.branch
+        );
+    } else {
+        println!("Successfully pushed {change_count} changes");
+    }
     println!("Synced to: {}", new_config.remote_url);
 
     Ok(())
 }
 
+fn push_args(branch: &str, force: bool) -> Vec<&str> {
+    let mut args = vec!["push"];
+    if force {
+        args.push("--force-with-lease");
+    }
+    args.extend(["origin", branch]);
+    args
+}
+
 pub fn pull_sync() -> anyhow::Result<()> {
     let sync_dir = get_sync_dir()?;
     let config = load_sync_config()?;
@@ -173,6 +493,8 @@ pub fn pull_sync() -> anyhow::Result<()> {
         anyhow::bail!("No remote configured. Add one with 'shorty sync remote add <url>'");
     }
 
+    ensure_branch(&sync_dir, &config.branch)?;
+
     let output = Command::new("git")
         .args(["fetch", "origin"])
         .current_dir(&sync_dir)
@@ -185,6 +507,26 @@ pub fn pull_sync() -> anyhow::Result<()> {
         );
     }
 
+    if let Some(metadata) = read_sync_metadata(&sync_dir) {
+        let aliases_path = get_aliases_path()?;
+        if calculate_checksum(&aliases_path)? != metadata.checksum {
+            println!("Warning: your local aliases have changed since the last sync.");
+            println!(
+                "Pulling will overwrite {} (a copy is kept as {}).",
+                aliases_path.display(),
+                aliases_path.with_extension("backup").display()
+            );
+            print!("Continue? (y/n): ");
+            io::stdout().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            if !input.trim().eq_ignore_ascii_case("y") {
+                println!("Pull aborted. Run 'shorty sync push' to upload your local changes first");
+                return Ok(());
+            }
+        }
+    }
+
     let local_changes = Command::new("git")
         .args(["status", "--porcelain"])
         .current_dir(&sync_dir)
@@ -251,16 +593,196 @@ pub fn pull_sync() -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn sync_status() -> anyhow::Result<()> {
+pub fn diff_sync(remote: bool) -> anyhow::Result<()> {
+    let sync_dir = get_sync_dir()?;
+    let config = load_sync_config()?;
+    let aliases_path = get_aliases_path()?;
+    let local = if aliases_path.exists() {
+        command_map(&read_aliases_file(&aliases_path)?)
+    } else {
+        BTreeMap::new()
+    };
+
+    let (label, content) = if remote {
+        if config.remote_url.is_empty() {
+            anyhow::bail!("No remote configured. Add one with 'shorty sync remote add <url>'");
+        }
+
+        fetch_remote_aliases(&sync_dir, &config)?
+    } else {
+        let sync_aliases_path = sync_dir.join("aliases");
+        let content = if sync_aliases_path.exists() {
+            read_aliases_file(&sync_aliases_path)?
+        } else {
+            String::new()
+        };
+        ("last synced copy".to_string(), content)
+    };
+
+    println!("Comparing local aliases -> {label}\n");
+    print_alias_diff(&local, &command_map(&content));
+
+    Ok(())
+}
+
+/// Name of the git remote whose URL is the configured sync remote, or "origin".
+fn configured_remote(sync_dir: &Path, remote_url: &str) -> String {
+    Command::new("git")
+        .args(["remote", "-v"])
+        .current_dir(sync_dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            parse_remotes(&String::from_utf8_lossy(&output.stdout))
+                .into_iter()
+                .find(|remote| remote.url == remote_url)
+                .map(|remote| remote.name)
+        })
+        .unwrap_or_else(|| "origin".to_string())
+}
+
+fn fetch_remote_aliases(sync_dir: &Path, config: &SyncConfig) -> anyhow::Result<(String, String)> {
+    let remote_name = configured_remote(sync_dir, &config.remote_url);
+
+    let output = Command::new("git")
+        .args(["fetch", &remote_name])
+        .current_dir(sync_dir)
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to fetch from remote: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let remote_ref = format!("{remote_name}/{}", config.branch);
+    let output = Command::new("git")
+        .args(["show", &format!("{remote_ref}:aliases")])
+        .current_dir(sync_dir)
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Remote branch '{}' has no aliases file: {}",
+            remote_ref,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok((
+        remote_ref,
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    ))
+}
+
+#[derive(Debug, Default, Serialize)]
+struct SyncStatus {
+    initialized: bool,
+    remote: Option<String>,
+    branch: Option<String>,
+    last_sync: Option<String>,
+    dirty: bool,
+    ahead: Option<usize>,
+    behind: Option<usize>,
+    changes: Vec<SyncChange>,
+}
+
+#[derive(Debug, Serialize)]
+struct SyncChange {
+    status: String,
+    file: String,
+}
+
+impl SyncChange {
+    fn description(&self) -> &'static str {
+        match self.status.as_str() {
+            "M" => "Modified",
+            "A" => "Added",
+            "D" => "Deleted",
+            "??" => "Untracked",
+            _ => "Changed",
+        }
+    }
+}
+
+fn parse_porcelain(output: &str) -> Vec<SyncChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let status = line.get(0..2)?.trim();
+            let file = line.get(3..)?;
+            Some(SyncChange {
+                status: status.to_string(),
+                file: file.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn parse_ahead_behind(output: &str) -> Option<(usize, usize)> {
+    let (ahead, behind) = output.trim().split_once('\t')?;
+    Some((ahead.parse().ok()?, behind.parse().ok()?))
+}
+
+fn collect_status(sync_dir: &Path, config: &SyncConfig) -> anyhow::Result<SyncStatus> {
+    let status_output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(sync_dir)
+        .output()?;
+    let changes = parse_porcelain(&String::from_utf8_lossy(&status_output.stdout));
+
+    let ahead_behind = if config.remote_url.is_empty() {
+        None
+    } else {
+        let remote_name = configured_remote(sync_dir, &config.remote_url);
+        Command::new("git")
+            .args([
+                "rev-list",
+                "--left-right",
+                "--count",
+                &format!("HEAD...{remote_name}/{}", config.branch),
+            ])
+            .current_dir(sync_dir)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| parse_ahead_behind(&String::from_utf8_lossy(&output.stdout)))
+    };
+
+    Ok(SyncStatus {
+        initialized: true,
+        remote: Some(config.remote_url.clone()).filter(|url| !url.is_empty()),
+        branch: Some(config.branch.clone()),
+        last_sync: Some(config.last_sync.clone()),
+        dirty: !changes.is_empty(),
+        ahead: ahead_behind.map(|(ahead, _)| ahead),
+        behind: ahead_behind.map(|(_, behind)| behind),
+        changes,
+    })
+}
+
+pub fn sync_status(output: OutputFormat) -> anyhow::Result<()> {
     let sync_dir = get_sync_dir()?;
 
     if !sync_dir.exists() {
-        println!("Sync not initialized");
-        println!("Run 'shorty sync init' to get started");
+        if output == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&SyncStatus::default())?);
+        } else {
+            println!("Sync not initialized");
+            println!("Run 'shorty sync init' to get started");
+        }
         return Ok(());
     }
 
     let config = load_sync_config()?;
+    let status = collect_status(&sync_dir, &config)?;
+
+    if output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
 
     println!("Sync Status:\n");
 
@@ -284,64 +806,38 @@ pub fn sync_status() -> anyhow::Result<()> {
         }
     );
 
-    let status_output = Command::new("git")
-        .args(["status", "--porcelain"])
-        .current_dir(&sync_dir)
-        .output()?;
-
-    if status_output.stdout.is_empty() {
+    let changes = &status.changes;
+    if changes.is_empty() {
         println!("Working tree clean - no changes to sync");
     } else {
-        let changes = String::from_utf8_lossy(&status_output.stdout);
-        let change_count = changes.lines().count();
-        println!("{change_count} uncommitted changes");
+        println!("{} uncommitted changes", changes.len());
 
         println!("\nChanges:");
-        for line in changes.lines().take(10) {
-            let status = &line[0..2];
-            let file = &line[3..];
-            let status_desc = match status.trim() {
-                "M" => "Modified",
-                "A" => "Added",
-                "D" => "Deleted",
-                "??" => "Untracked",
-                _ => "Changed",
-            };
-            println!("  {status_desc} {file}");
+        for change in changes.iter().take(10) {
+            println!("  {} {}", change.description(), change.file);
         }
 
-        if change_count > 10 {
-            println!("  ... and {} more", change_count - 10);
+        if changes.len() > 10 {
+            println!("  ... and {} more", changes.len() - 10);
         }
     }
 
     if !config.remote_url.is_empty() {
         println!("\nRemote Status:");
 
-        let ahead_behind = Command::new("git")
-            .args(["rev-list", "--left-right", "--count", "HEAD...origin/main"])
-            .current_dir(&sync_dir)
-            .output();
-
-        match ahead_behind {
-            Ok(output) if output.status.success() => {
-                let counts = String::from_utf8_lossy(&output.stdout);
-                let parts: Vec<&str> = counts.trim().split('\t').collect();
-                if parts.len() == 2 {
-                    let ahead = parts[0];
-                    let behind = parts[1];
-                    println!("  {ahead} commits ahead");
-                    println!("  {behind} commits behind");
-
-                    if ahead != "0" {
-                        println!("Run 'shorty sync push' to upload your changes");
-                    }
-                    if behind != "0" {
-                        println!("Run 'shorty sync pull' to get remote changes");
-                    }
+        match status.ahead.zip(status.behind) {
+            Some((ahead, behind)) => {
+                println!("  {ahead} commits ahead");
+                println!("  {behind} commits behind");
+
+                if ahead != 0 {
+                    println!("Run 'shorty sync push' to upload your changes");
+                }
+                if behind != 0 {
+                    println!("Run 'shorty sync pull' to get remote changes");
                 }
             }
-            _ => {
+            None => {
                 println!("  Unable to check remote status (fetch first)");
             }
         }
@@ -350,14 +846,24 @@ pub fn sync_status() -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn share_alias(alias_name: &str, method: &str) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
+pub fn share_alias(
+    alias_name: &str,
+    method: &str,
+    output: Option<&str>,
+    output_dir: Option<&str>,
+    format: Option<ShareFormat>,
+) -> anyhow::Result<()> {
+    if format.is_some() && method != "file" {
+        anyhow::bail!("--format is only supported with --method file");
+    }
+
+    let aliases_path = get_aliases_path()?;
 
     if !aliases_path.exists() {
         anyhow::bail!("No aliases file found");
     }
 
-    let content = fs::read_to_string(&aliases_path)?;
+    let content = read_aliases_file(&aliases_path)?;
     let mut alias_line = None;
 
     for line in content.lines() {
@@ -372,39 +878,31 @@ pub fn share_alias(alias_name: &str, method: &str) -> anyhow::Result<()> {
 
     match method {
         "clipboard" => {
-            let copy_cmd = if cfg!(target_os = "macos") {
-                "pbcopy"
-            } else if cfg!(target_os = "linux") {
-                "xclip -selection clipboard"
-            } else {
-                anyhow::bail!("Clipboard sharing not supported on this platform");
-            };
-
-            let output = if cfg!(target_os = "macos") {
-                Command::new("pbcopy").arg(alias_line).output()?
-            } else {
-                Command::new("sh")
-                    .args(["-c", &format!("echo '{alias_line}' | {copy_cmd}")])
-                    .output()?
-            };
+            copy_to_clipboard(alias_line).map_err(|err| {
+                anyhow::anyhow!("{}. Use --method file or --method qr instead", err)
+            })?;
 
-            if output.status.success() {
-                println!("Alias copied to clipboard:");
-                println!("{alias_line}");
-            } else {
-                anyhow::bail!("Failed to copy to clipboard");
-            }
+            println!("Alias copied to clipboard:");
+            println!("{alias_line}");
         }
         "qr" => {
-            generate_qr_code(alias_line)?;
+            generate_qr_code(alias_line, output, output_dir)?;
         }
         "file" => {
-            let share_file = format!("shorty_share_{alias_name}.sh");
-            let content = format!("#!/bin/bash\n# Shared alias from Shorty\n{alias_line}\n");
+            let format = format.unwrap_or(ShareFormat::Bash);
+            let alias = parse_content(&content)
+                .into_iter()
+                .find(|alias| alias.name == alias_name)
+                .ok_or_else(|| anyhow::anyhow!("Alias '{}' not found", alias_name))?;
+            let content = format.render(&alias)?;
+            let share_file = resolve_output_path(
+                output_dir,
+                &format!("shorty_share_{alias_name}.{}", format.extension()),
+            )?;
             fs::write(&share_file, content)?;
 
-            println!("Alias saved to: {share_file}");
-            println!("Share this file or run it to add the alias");
+            println!("Alias saved to: {}", share_file.display());
+            println!("Share this file or source it to add the alias");
         }
         _ => {
             anyhow::bail!(
@@ -463,6 +961,90 @@ pub fn add_remote(url: &str, name: Option<&str>) -> anyhow::Result<()> {
     Ok(())
 }
 
+struct SyncRemote {
+    name: String,
+    url: String,
+    direction: String,
+}
+
+fn parse_remotes(output: &str) -> Vec<SyncRemote> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.split_once('\t')?;
+            let (url, direction) = rest.rsplit_once(' ')?;
+            Some(SyncRemote {
+                name: name.to_string(),
+                url: url.to_string(),
+                direction: direction.trim_matches(|c| c == '(' || c == ')').to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn list_remotes() -> anyhow::Result<()> {
+    let sync_dir = get_sync_dir()?;
+
+    if !sync_dir.join(".git").exists() {
+        anyhow::bail!("Sync not initialized. Run 'shorty sync init' first");
+    }
+
+    let config = load_sync_config()?;
+
+    let output = Command::new("git")
+        .args(["remote", "-v"])
+        .current_dir(&sync_dir)
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to list remotes: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let remotes = parse_remotes(&String::from_utf8_lossy(&output.stdout));
+    if remotes.is_empty() {
+        println!("No remotes configured. Add one with 'shorty sync remote add <url>'");
+        return Ok(());
+    }
+
+    let name_width = remotes
+        .iter()
+        .map(|remote| remote.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let url_width = remotes
+        .iter()
+        .map(|remote| remote.url.len())
+        .max()
+        .unwrap_or(0)
+        .max(3);
+
+    println!(
+        "  {:<name_width$}  {:<url_width$}  DIRECTION",
+        "NAME", "URL"
+    );
+    for remote in &remotes {
+        let marker = if remote.url == config.remote_url {
+            '*'
+        } else {
+            ' '
+        };
+        println!(
+            "{marker} {:<name_width$}  {:<url_width$}  {}",
+            remote.name, remote.url, remote.direction
+        );
+    }
+
+    if !config.remote_url.is_empty() {
+        println!("\n* configured sync remote");
+    }
+
+    Ok(())
+}
+
 pub fn reset_sync() -> anyhow::Result<()> {
     let sync_dir = get_sync_dir()?;
 
@@ -484,6 +1066,44 @@ fn get_sync_dir() -> anyhow::Result<PathBuf> {
     Ok(home_dir.join(".shorty").join("sync"))
 }
 
+fn ensure_branch(sync_dir: &Path, branch: &str) -> anyhow::Result<()> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--short", "HEAD"])
+        .current_dir(sync_dir)
+        .output()?;
+    let current = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    if !output.status.success() || current != branch {
+        anyhow::bail!(
+            "Sync repository is on branch '{}' but sync is configured for '{}'. Check out '{}' in {} or update the branch in sync_config.toml",
+            current,
+            branch,
+            branch,
+            sync_dir.display()
+        );
+    }
+
+    let output = Command::new("git")
+        .args([
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{branch}"),
+        ])
+        .current_dir(sync_dir)
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Branch '{}' has no commits in {}",
+            branch,
+            sync_dir.display()
+        );
+    }
+
+    Ok(())
+}
+
 fn load_sync_config() -> anyhow::Result<SyncConfig> {
     let config_path = get_sync_dir()?.join("sync_config.toml");
 
@@ -507,7 +1127,7 @@ fn save_sync_config(config: &SyncConfig) -> anyhow::Result<()> {
 }
 
 fn copy_aliases_to_sync_dir(sync_dir: &Path) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
+    let aliases_path = get_aliases_path()?;
     let sync_aliases_path = sync_dir.join("aliases");
 
     if aliases_path.exists() {
@@ -533,7 +1153,7 @@ fn copy_aliases_to_sync_dir(sync_dir: &Path) -> anyhow::Result<()> {
 }
 
 fn copy_aliases_from_sync_dir(sync_dir: &Path) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
+    let aliases_path = get_aliases_path()?;
     let sync_aliases_path = sync_dir.join("aliases");
 
     if sync_aliases_path.exists() {
@@ -542,7 +1162,7 @@ fn copy_aliases_from_sync_dir(sync_dir: &Path) -> anyhow::Result<()> {
             fs::copy(&aliases_path, &backup_path)?;
         }
 
-        fs::copy(&sync_aliases_path, &aliases_path)?;
+        write_aliases_atomic(&read_aliases_file(&sync_aliases_path)?)?;
     }
 
     Ok(())
@@ -591,7 +1211,7 @@ fn count_aliases(path: &Path) -> anyhow::Result<usize> {
         return Ok(0);
     }
 
-    let content = fs::read_to_string(path)?;
+    let content = read_aliases_file(path)?;
     let count = content
         .lines()
         .filter(|line| line.trim().starts_with("alias "))
@@ -601,29 +1221,290 @@ fn count_aliases(path: &Path) -> anyhow::Result<usize> {
 }
 
 fn calculate_checksum(path: &Path) -> anyhow::Result<String> {
-    if !path.exists() {
-        return Ok("0".to_string());
+    let content = if path.exists() {
+        fs::read(path)?
+    } else {
+        Vec::new()
+    };
+
+    Ok(format!("{:x}", Sha256::digest(&content)))
+}
+
+fn read_sync_metadata(sync_dir: &Path) -> Option<SyncMetadata> {
+    let content = fs::read_to_string(sync_dir.join("metadata.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+const QR_PART_BYTES: usize = 1000;
+
+fn split_qr_payload(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+
+    while rest.len() > QR_PART_BYTES {
+        let mut split = QR_PART_BYTES;
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        let (part, tail) = rest.split_at(split);
+        parts.push(part);
+        rest = tail;
     }
+    parts.push(rest);
 
-    let content = fs::read_to_string(path)?;
-    let hash = content.len();
+    parts
+}
 
-    Ok(hash.to_string())
+fn qr_part_path(path: &Path, part: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "shorty_alias_qr".to_string());
+    path.with_file_name(format!("{stem}_part{part}.png"))
 }
 
-fn generate_qr_code(text: &str) -> anyhow::Result<()> {
-    println!("QR Code for alias:");
-    println!("┌─────────────────────────────────────┐");
-    println!("│  QR code would be generated here    │");
-    println!("│  Alias: {text}                          │");
-    println!("│                                     │");
-    println!("│  Use a proper QR code library for   │");
-    println!("│  actual QR code generation          │");
-    println!("└─────────────────────────────────────┘");
+pub(crate) fn copy_to_clipboard(text: &str) -> anyhow::Result<()> {
+    #[allow(unused_mut)]
+    let mut clipboard =
+        Clipboard::new().map_err(|err| anyhow::anyhow!("No clipboard available ({})", err))?;
+
+    // On Linux the clipboard contents belong to the process that set them, so
+    // a detached `shorty __clipboard` child keeps serving them after we exit.
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::process::CommandExt;
+        use std::process::Stdio;
+
+        drop(clipboard);
+        let mut child = Command::new(std::env::current_exe()?)
+            .arg("__clipboard")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()
+            .map_err(|err| anyhow::anyhow!("Failed to copy to clipboard: {}", err))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    clipboard
+        .set_text(text)
+        .map_err(|err| anyhow::anyhow!("Failed to copy to clipboard: {}", err))
+}
+
+pub fn serve_clipboard() -> anyhow::Result<()> {
+    let mut text = String::new();
+    io::Read::read_to_string(&mut io::stdin(), &mut text)?;
+    let mut clipboard = Clipboard::new()?;
+
+    #[cfg(target_os = "linux")]
+    {
+        use arboard::SetExtLinux;
+        clipboard.set().wait().text(text)?;
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    clipboard.set_text(text)?;
+
+    Ok(())
+}
+
+fn generate_qr_code(
+    text: &str,
+    output: Option<&str>,
+    output_dir: Option<&str>,
+) -> anyhow::Result<()> {
+    let codes = match QrCode::with_error_correction_level(text, EcLevel::L) {
+        Ok(code) => vec![code],
+        Err(QrError::DataTooLong) => {
+            let parts = split_qr_payload(text);
+            println!(
+                "Warning: alias is too long for a single QR code; splitting it into {} parts.",
+                parts.len()
+            );
+            println!("Scan the parts in order and join them to get the full alias line.");
+            parts
+                .into_iter()
+                .map(|part| QrCode::with_error_correction_level(part, EcLevel::L))
+                .collect::<Result<_, _>>()?
+        }
+        Err(err) => anyhow::bail!("Failed to generate QR code: {}", err),
+    };
+
+    let png_path = match output {
+        Some(path) => Some(expand_home(path)),
+        None if output_dir.is_some() => {
+            Some(resolve_output_path(output_dir, "shorty_alias_qr.png")?)
+        }
+        None => None,
+    };
+
+    println!("QR code for alias:");
+    for (i, code) in codes.iter().enumerate() {
+        if codes.len() > 1 {
+            println!("Part {}/{}:", i + 1, codes.len());
+        }
+        let rendered = code
+            .render::<unicode::Dense1x2>()
+            .dark_color(unicode::Dense1x2::Light)
+            .light_color(unicode::Dense1x2::Dark)
+            .build();
+        println!("{rendered}");
+
+        if let Some(path) = &png_path {
+            let path = if codes.len() > 1 {
+                qr_part_path(path, i + 1)
+            } else {
+                path.clone()
+            };
+            code.render::<Luma<u8>>()
+                .min_dimensions(256, 256)
+                .build()
+                .save(&path)
+                .with_context(|| format!("Failed to write QR code to {}", path.display()))?;
+            println!("QR code saved to: {}", path.display());
+        }
+    }
 
-    let qr_file = "shorty_alias_qr.txt";
-    fs::write(qr_file, text)?;
-    println!("Alias saved to: {qr_file}");
+    println!("{text}");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn force_push_uses_force_with_lease() {
+        assert_eq!(push_args("main", false), ["push", "origin", "main"]);
+        assert_eq!(
+            push_args("main", true),
+            ["push", "--force-with-lease", "origin", "main"]
+        );
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn test_config(remote_url: &str) -> SyncConfig {
+        SyncConfig {
+            remote_url: remote_url.to_string(),
+            branch: "main".to_string(),
+            last_sync: "2024-01-01 00:00:00".to_string(),
+            auto_sync: false,
+            sync_interval: 0,
+        }
+    }
+
+    #[test]
+    fn status_json_reports_a_dirty_working_tree() {
+        let dir = std::env::temp_dir().join(format!("shorty-status-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]);
+        fs::write(dir.join("aliases"), "alias gs='git status'\n").unwrap();
+        git(&dir, &["add", "aliases"]);
+        git(&dir, &["commit", "-q", "-m", "initial"]);
+        fs::write(dir.join("aliases"), "alias gs='git status -s'\n").unwrap();
+        fs::write(dir.join("notes"), "scratch\n").unwrap();
+
+        let status = collect_status(&dir, &test_config("")).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            serde_json::json!({
+                "initialized": true,
+                "remote": null,
+                "branch": "main",
+                "last_sync": "2024-01-01 00:00:00",
+                "dirty": true,
+                "ahead": null,
+                "behind": null,
+                "changes": [
+                    {"status": "M", "file": "aliases"},
+                    {"status": "??", "file": "notes"},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn remote_diff_reads_divergent_aliases_from_the_configured_remote() {
+        let root = std::env::temp_dir().join(format!("shorty-sync-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let (bare, local, other) = (
+            root.join("bare.git"),
+            root.join("local"),
+            root.join("other"),
+        );
+        for dir in [&bare, &local, &other] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let url = bare.display().to_string();
+
+        git(&bare, &["init", "--bare", "-q", "-b", "main"]);
+        for dir in [&local, &other] {
+            git(dir, &["init", "-q", "-b", "main"]);
+            git(dir, &["remote", "add", "upstream", &url]);
+        }
+
+        fs::write(local.join("aliases"), "alias gs='git status'\n").unwrap();
+        git(&local, &["add", "aliases"]);
+        git(&local, &["commit", "-q", "-m", "local"]);
+        git(&local, &["push", "-q", "upstream", "main"]);
+
+        git(&other, &["pull", "-q", "upstream", "main"]);
+        fs::write(
+            other.join("aliases"),
+            "alias gs='git status -s'\nalias ll='ls -la'\n",
+        )
+        .unwrap();
+        git(&other, &["commit", "-q", "-am", "other"]);
+        git(&other, &["push", "-q", "upstream", "main"]);
+
+        let config = test_config(&url);
+        let (label, content) = fetch_remote_aliases(&local, &config).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(label, "upstream/main");
+        let remote = command_map(&content);
+        assert_eq!(remote.get("gs").map(String::as_str), Some("git status -s"));
+        assert_eq!(remote.get("ll").map(String::as_str), Some("ls -la"));
+    }
+
+    #[test]
+    fn fish_share_file_uses_fish_syntax_and_extension() {
+        let home = crate::utils::test_support::FakeHome::new("share-fish");
+        home.write("aliases", "alias gs='git status'\nalias ll='ls -la'\n");
+        let out_dir = home.path().join("shared");
+
+        share_alias(
+            "gs",
+            "file",
+            None,
+            out_dir.to_str(),
+            Some(ShareFormat::Fish),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(out_dir.join("shorty_share_gs.fish")).unwrap();
+        assert!(content.starts_with("#!/usr/bin/env fish\n"), "{content}");
+        assert!(content.contains("alias gs 'git status'"), "{content}");
+        assert!(!content.contains("alias gs="), "{content}");
+        assert!(!out_dir.join("shorty_share_gs.sh").exists());
+    }
+}
@@ -0,0 +1,310 @@
+use crate::alias::{parse_content, parse_entries};
+use crate::commands::backup::backup_before_edit;
+use crate::commands::history::record_operation;
+use crate::utils::{
+    acquire_lock, get_aliases_path, read_aliases_file, read_aliases_sources, write_aliases_atomic,
+    OutputFormat,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum TagSort {
+    Count,
+    Name,
+}
+
+impl std::str::FromStr for TagSort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "count" => Ok(TagSort::Count),
+            "name" => Ok(TagSort::Name),
+            _ => anyhow::bail!("Unsupported sort order: {}. Supported: count, name", s),
+        }
+    }
+}
+
+pub fn list_tags(sort: TagSort, output: OutputFormat) -> anyhow::Result<()> {
+    let tags = count_tags(&read_aliases_sources()?, sort);
+
+    if tags.is_empty() && output == OutputFormat::Text {
+        println!("No tags found.");
+        return Ok(());
+    }
+
+    if output == OutputFormat::Json {
+        let value: Vec<serde_json::Value> = tags
+            .iter()
+            .map(|(tag, count)| serde_json::json!({ "tag": tag, "count": count }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    let width = tags
+        .iter()
+        .map(|(tag, _)| tag.len())
+        .max()
+        .unwrap_or(0)
+        .max(3);
+    println!("{:<width$}  COUNT", "TAG");
+    for (tag, count) in &tags {
+        println!("{tag:<width$}  {count}");
+    }
+
+    Ok(())
+}
+
+fn count_tags(sources: &[(PathBuf, String)], sort: TagSort) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for (_, content) in sources {
+        for alias in parse_content(content) {
+            for tag in alias.tags {
+                *counts.entry(tag).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+    match sort {
+        TagSort::Count => tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0))),
+        TagSort::Name => tags.sort_by(|a, b| a.0.cmp(&b.0)),
+    }
+    tags
+}
+
+fn validate_tag_name(name: &str) -> anyhow::Result<&str> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(',') || name.contains(char::is_whitespace) {
+        anyhow::bail!("Invalid tag name: '{}'", name);
+    }
+    Ok(name)
+}
+
+fn rewrite_tags(
+    operation: &str,
+    details: &str,
+    update: impl Fn(&[String]) -> Option<Vec<String>>,
+) -> anyhow::Result<Option<usize>> {
+    let _lock = acquire_lock()?;
+    let aliases_path = get_aliases_path()?;
+
+    if !aliases_path.exists() {
+        println!("No aliases file found.");
+        return Ok(None);
+    }
+
+    let contents = read_aliases_file(&aliases_path)?;
+    let mut replacements: HashMap<usize, (usize, String)> = HashMap::new();
+
+    for mut entry in parse_entries(&contents) {
+        let Some(tags) = update(&entry.alias.tags) else {
+            continue;
+        };
+        let mut deduped: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            if !deduped.contains(&tag) {
+                deduped.push(tag);
+            }
+        }
+        entry.alias.tags = deduped;
+
+        replacements.insert(entry.line_number, (entry.line_count, entry.to_line()));
+    }
+
+    if replacements.is_empty() {
+        return Ok(Some(0));
+    }
+
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut new_lines: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut i = 0;
+
+    while i < lines.len() {
+        match replacements.get(&(i + 1)) {
+            Some((line_count, new_line)) => {
+                new_lines.push(new_line);
+                i += line_count;
+            }
+            None => {
+                new_lines.push(lines[i]);
+                i += 1;
+            }
+        }
+    }
+
+    let mut new_contents = new_lines.join("\n");
+    new_contents.push('\n');
+
+    backup_before_edit()?;
+    write_aliases_atomic(&new_contents)?;
+    record_operation(operation, details);
+
+    Ok(Some(replacements.len()))
+}
+
+pub fn rename_tag(old: &str, new: &str) -> anyhow::Result<()> {
+    let new = validate_tag_name(new)?;
+    if old == new {
+        println!("Tag '{old}' is unchanged.");
+        return Ok(());
+    }
+
+    let updated = rewrite_tags("tag-rename", &format!("{old} -> {new}"), |tags| {
+        tags.iter().any(|tag| tag == old).then(|| {
+            tags.iter()
+                .map(|tag| {
+                    if tag == old {
+                        new.to_string()
+                    } else {
+                        tag.clone()
+                    }
+                })
+                .collect()
+        })
+    })?;
+
+    match updated {
+        None => {}
+        Some(0) => println!("No aliases are tagged '{old}'."),
+        Some(count) => {
+            println!("Renamed tag '{old}' to '{new}' on {count} alias(es).");
+            println!("To apply the changes, please restart your terminal!");
+        }
+    }
+
+    Ok(())
+}
+
+pub fn delete_tag(name: &str) -> anyhow::Result<()> {
+    let updated = rewrite_tags("tag-delete", name, |tags| {
+        tags.iter()
+            .any(|tag| tag == name)
+            .then(|| tags.iter().filter(|tag| *tag != name).cloned().collect())
+    })?;
+
+    match updated {
+        None => {}
+        Some(0) => println!("No aliases are tagged '{name}'."),
+        Some(count) => {
+            println!("Removed tag '{name}' from {count} alias(es).");
+            println!("To apply the changes, please restart your terminal!");
+        }
+    }
+
+    Ok(())
+}
+
+pub fn merge_tags(first: &str, second: &str, into: &str) -> anyhow::Result<()> {
+    let into = validate_tag_name(into)?;
+    let sources = [first, second];
+
+    let updated = rewrite_tags(
+        "tag-merge",
+        &format!("{first}, {second} -> {into}"),
+        |tags| {
+            tags.iter()
+                .any(|tag| sources.contains(&tag.as_str()))
+                .then(|| {
+                    tags.iter()
+                        .map(|tag| {
+                            if sources.contains(&tag.as_str()) {
+                                into.to_string()
+                            } else {
+                                tag.clone()
+                            }
+                        })
+                        .collect()
+                })
+        },
+    )?;
+
+    match updated {
+        None => {}
+        Some(0) => println!("No aliases are tagged '{first}' or '{second}'."),
+        Some(count) => {
+            println!("Merged tags '{first}' and '{second}' into '{into}' on {count} alias(es).");
+            println!("To apply the changes, please restart your terminal!");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::FakeHome;
+    use std::fs;
+
+    #[test]
+    fn rename_rewrites_every_occurrence_of_the_tag() {
+        let home = FakeHome::new("tag-rename");
+        let aliases_path = home.write(
+            "aliases",
+            "alias gs='git status' #tags:git,vcs\n\
+             alias ll='ls -la' #tags:fs\n\
+             # sync it\n\
+             sync() { #tags:vcs,remote\n    git pull\n}\n\
+             alias gl='git log' # history #tags:scm,vcs\n",
+        );
+
+        rename_tag("vcs", "scm").unwrap();
+
+        let contents = fs::read_to_string(&aliases_path).unwrap();
+        let tags: Vec<(String, Vec<String>)> = parse_content(&contents)
+            .into_iter()
+            .map(|alias| (alias.name, alias.tags))
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                ("gs".to_string(), vec!["git".to_string(), "scm".to_string()]),
+                ("ll".to_string(), vec!["fs".to_string()]),
+                (
+                    "sync".to_string(),
+                    vec!["scm".to_string(), "remote".to_string()]
+                ),
+                ("gl".to_string(), vec!["scm".to_string()]),
+            ]
+        );
+        assert!(!contents.contains("vcs"), "{contents}");
+        assert!(
+            contents.ends_with("}\nalias gl='git log' # history #tags:scm\n"),
+            "{contents}"
+        );
+    }
+
+    #[test]
+    fn counts_add_up_across_aliases_and_files() {
+        let sources = [
+            (
+                PathBuf::from("aliases"),
+                "alias gs='git status' #tags:git,vcs\n\
+                 alias gp='git push' #tags:git,remote\n\
+                 alias ll='ls -la' #tags:ops\n"
+                    .to_string(),
+            ),
+            (
+                PathBuf::from("team"),
+                "alias gf='git fetch' #tags:git,remote,vcs\n".to_string(),
+            ),
+        ];
+        let counts = |sort| -> Vec<(String, usize)> { count_tags(&sources, sort) };
+        let expected = |pairs: &[(&str, usize)]| -> Vec<(String, usize)> {
+            pairs.iter().map(|(tag, n)| (tag.to_string(), *n)).collect()
+        };
+
+        assert_eq!(
+            counts(TagSort::Count),
+            expected(&[("git", 3), ("remote", 2), ("vcs", 2), ("ops", 1)])
+        );
+        assert_eq!(
+            counts(TagSort::Name),
+            expected(&[("git", 3), ("ops", 1), ("remote", 2), ("vcs", 2)])
+        );
+    }
+}
@@ -1,6 +1,17 @@
+use crate::alias::{self, parse_content, Alias};
+use crate::utils::{acquire_lock, expand_home, read_aliases_file, write_atomic, OutputFormat};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fs, path::PathBuf};
 
+#[derive(Debug, Default)]
+pub enum TemplateTarget {
+    #[default]
+    Aliases,
+    Command,
+    Snippet,
+    File(String),
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Template {
     pub name: String,
@@ -66,9 +77,18 @@ pub fn add_template(
     Ok(())
 }
 
-pub fn list_templates(category: Option<&str>) -> anyhow::Result<()> {
+pub fn list_templates(category: Option<&str>, output: OutputFormat) -> anyhow::Result<()> {
     let templates = load_templates()?;
 
+    if output == OutputFormat::Json {
+        let filtered: Vec<&Template> = templates
+            .iter()
+            .filter(|t| category.is_none_or(|cat| t.category == cat))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&filtered)?);
+        return Ok(());
+    }
+
     if templates.is_empty() {
         println!("No templates found. Create your first template with 'shorty template add'");
         return Ok(());
@@ -123,6 +143,8 @@ pub fn use_template(
     name: &str,
     params: &HashMap<String, String>,
     alias_name: Option<&str>,
+    target: &TemplateTarget,
+    all_defaults: bool,
 ) -> anyhow::Result<()> {
     let mut templates = load_templates()?;
 
@@ -131,8 +153,42 @@ pub fn use_template(
         .find(|t| t.name == name)
         .ok_or_else(|| anyhow::anyhow!("Template '{}' not found", name))?;
 
+    let mut unknown_params: Vec<&str> = params
+        .keys()
+        .filter(|key| !template.parameters.iter().any(|p| &p.name == *key))
+        .map(|key| key.as_str())
+        .collect();
+    if !unknown_params.is_empty() {
+        unknown_params.sort();
+        let valid_names: Vec<&str> = template
+            .parameters
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        anyhow::bail!(
+            "Unknown parameter(s) for template '{}': {}. Valid parameters: {}",
+            template.name,
+            unknown_params.join(", "),
+            if valid_names.is_empty() {
+                "(none)".to_string()
+            } else {
+                valid_names.join(", ")
+            }
+        );
+    }
+
+    let mut defaults_used = Vec::new();
     for param in &template.parameters {
-        if param.required && !params.contains_key(&param.name) {
+        if params.contains_key(&param.name) {
+            continue;
+        }
+        if all_defaults {
+            if let Some(default) = &param.default_value {
+                defaults_used.push(format!("{}={}", param.name, default));
+                continue;
+            }
+        }
+        if param.required {
             anyhow::bail!(
                 "Required parameter '{}' is missing. Description: {}",
                 param.name,
@@ -140,6 +196,10 @@ pub fn use_template(
             );
         }
     }
+    let prints_output = matches!(target, TemplateTarget::Command | TemplateTarget::Snippet);
+    if !defaults_used.is_empty() && !prints_output {
+        println!("Using defaults: {}", defaults_used.join(", "));
+    }
 
     let mut command = template.pattern.clone();
     for param in &template.parameters {
@@ -174,6 +234,11 @@ pub fn use_template(
         anyhow::bail!("Missing values for parameters: {}", param_names.join(", "));
     }
 
+    if let TemplateTarget::Command = target {
+        println!("{command}");
+        return Ok(());
+    }
+
     let final_alias_name = if let Some(name) = alias_name {
         name.to_string()
     } else {
@@ -186,23 +251,76 @@ pub fn use_template(
         auto_name
     };
 
-    crate::commands::add::add_alias(
-        &final_alias_name,
-        &command,
-        &Some(format!("Generated from template: {}", template.name)),
-        &[template.category.clone(), "template".to_string()],
-    )?;
+    let entry = Alias {
+        name: final_alias_name.clone(),
+        command: command.clone(),
+        note: Some(format!("Generated from template: {}", template.name)),
+        tags: vec![template.category.clone(), "template".to_string()],
+        category: None,
+    };
+
+    match target {
+        TemplateTarget::Snippet => {
+            println!("{}", alias::to_line(&entry));
+            return Ok(());
+        }
+        TemplateTarget::File(path) => append_to_file(&expand_home(path), &entry)?,
+        _ => crate::commands::add::add_alias(
+            &entry.name,
+            &entry.command,
+            &entry.note,
+            &entry.tags,
+            &crate::commands::add::AddOptions::default(),
+        )?,
+    }
 
     let template_name = template.name.clone();
     template.usage_count += 1;
     save_templates(&templates)?;
 
-    println!("Alias '{final_alias_name}' created from template '{template_name}'");
+    match target {
+        TemplateTarget::File(path) => {
+            println!("Alias '{final_alias_name}' from template '{template_name}' written to {path}")
+        }
+        _ => println!("Alias '{final_alias_name}' created from template '{template_name}'"),
+    }
     println!("Command: {command}");
 
     Ok(())
 }
 
+fn append_to_file(path: &std::path::Path, entry: &Alias) -> anyhow::Result<()> {
+    let _lock = acquire_lock()?;
+
+    let mut content = if path.exists() {
+        read_aliases_file(path)?
+    } else {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        String::new()
+    };
+
+    if parse_content(&content)
+        .iter()
+        .any(|existing| existing.name == entry.name)
+    {
+        anyhow::bail!(
+            "Alias '{}' already exists in {}",
+            entry.name,
+            path.display()
+        );
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&alias::to_line(entry));
+    content.push('\n');
+
+    write_atomic(path, &content)
+}
+
 pub fn remove_template(name: &str) -> anyhow::Result<()> {
     let mut templates = load_templates()?;
 
@@ -515,3 +633,70 @@ fn create_default_templates() -> Vec<Template> {
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::parse_content;
+    use crate::utils::test_support::FakeHome;
+
+    #[test]
+    fn docker_run_needs_only_the_image_with_all_defaults() {
+        let home = FakeHome::new("template-defaults");
+        let params = HashMap::from([("image".to_string(), "nginx".to_string())]);
+
+        use_template("docker_run", &params, None, &TemplateTarget::Aliases, true).unwrap();
+
+        let content = fs::read_to_string(home.path().join(".shorty").join("aliases")).unwrap();
+        let aliases = parse_content(&content);
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].name, "docker_run");
+        assert_eq!(aliases[0].command, "docker run -it --rm  nginx /bin/bash");
+    }
+
+    #[test]
+    fn unknown_params_are_rejected_with_the_valid_names() {
+        let home = FakeHome::new("template-unknown-param");
+        let params = HashMap::from([
+            ("image".to_string(), "nginx".to_string()),
+            ("imgae".to_string(), "typo".to_string()),
+        ]);
+
+        let error =
+            use_template("docker_run", &params, None, &TemplateTarget::Aliases, true).unwrap_err();
+
+        let message = error.to_string();
+        assert!(
+            message.starts_with("Unknown parameter(s) for template 'docker_run': imgae."),
+            "{message}"
+        );
+        assert!(message.contains("Valid parameters: "), "{message}");
+        assert!(message.contains("image"), "{message}");
+        assert!(!home.path().join(".shorty").join("aliases").exists());
+    }
+
+    #[test]
+    fn file_target_appends_the_alias_to_that_file_only() {
+        let home = FakeHome::new("template-file-target");
+        let target = home.path().join("project").join(".aliases");
+        fs::create_dir_all(target.parent().unwrap()).unwrap();
+        fs::write(&target, "alias ll='ls -la'\n").unwrap();
+        let params = HashMap::from([("image".to_string(), "nginx".to_string())]);
+
+        use_template(
+            "docker_run",
+            &params,
+            Some("web"),
+            &TemplateTarget::File(target.to_str().unwrap().to_string()),
+            true,
+        )
+        .unwrap();
+
+        let aliases = parse_content(&fs::read_to_string(&target).unwrap());
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[0].name, "ll");
+        assert_eq!(aliases[1].name, "web");
+        assert_eq!(aliases[1].command, "docker run -it --rm  nginx /bin/bash");
+        assert!(!home.path().join(".shorty").join("aliases").exists());
+    }
+}
@@ -16,9 +16,9 @@ pub fn uninstall() -> anyhow::Result<()> {
         println!("shorty binary not found in /usr/local/bin.");
     }
 
-    let aliases_path = dirs::home_dir()
-        .expect("Could not find home directory")
-        .join(".shorty_aliases");
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    let aliases_path = home_dir.join(".shorty_aliases");
     if aliases_path.exists() {
         print!("Do you want to remove the ~/.shorty_aliases file? (y/n): ");
         io::stdout().flush()?;
@@ -37,12 +37,8 @@ pub fn uninstall() -> anyhow::Result<()> {
     let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
     let shell_name = shell.split('/').next_back().unwrap_or("sh");
     let config_file = match shell_name {
-        "zsh" => dirs::home_dir()
-            .expect("Could not find home directory")
-            .join(".zshrc"),
-        "bash" => dirs::home_dir()
-            .expect("Could not find home directory")
-            .join(".bashrc"),
+        "zsh" => home_dir.join(".zshrc"),
+        "bash" => home_dir.join(".bashrc"),
         _ => {
             println!("Unsupported shell: {shell_name}. Please manually remove 'source ~/.shorty_aliases' from your shell configuration.");
             return Ok(());
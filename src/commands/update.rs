@@ -1,17 +1,17 @@
 use anyhow::{Context, Result};
 use std::io::{self, Write};
 use crate::updater::{
-    get_latest_release, compare_versions, current_version, find_asset_url,
-    VersionComparison, download_binary, get_temp_download_path, backup_current_binary,
-    install_binary, verify_binary, cleanup_max_backups,
+    get_latest_release, compare_versions, current_version, find_asset_url, find_checksum_url,
+    get_platform_binary_name, VersionComparison, download_binary, download_checksum,
+    verify_checksum, get_temp_download_path, backup_current_binary, install_binary,
+    verify_binary, cleanup_max_backups,
 };
 use crate::utils::update_state;
 
 pub fn run_update(check_only: bool, force: bool) -> Result<()> {
     println!("Checking for updates...");
 
-    let release = get_latest_release(30)
-        .context("Failed to check for updates. Please check your internet connection.")?;
+    let release = get_latest_release(30, 2).context("Failed to check for updates")?;
 
     let current = current_version();
     let latest = &release.tag_name;
@@ -30,7 +30,7 @@ pub fn run_update(check_only: bool, force: bool) -> Result<()> {
             println!("✓ You are running a development version ahead of the latest release.");
             return Ok(());
         }
-        VersionComparison::UpdateAvailable | _ => {
+        _ => {
             if !release.body.is_empty() {
                 println!("\nChangelog:");
                 println!("{}", format_changelog(&release.body));
@@ -79,6 +79,7 @@ fn perform_update(release: &crate::updater::Release, current_version: &str) -> R
 
     println!("2. Finding download URL...");
     let download_url = find_asset_url(release)?;
+    let checksum_url = find_checksum_url(release)?;
     println!("   URL: {}", download_url);
 
     println!("3. Downloading new binary...");
@@ -86,17 +87,22 @@ fn perform_update(release: &crate::updater::Release, current_version: &str) -> R
     download_binary(&download_url, &temp_path)?;
     println!("   Downloaded to: {:?}", temp_path);
 
-    println!("4. Verifying new binary...");
+    println!("4. Verifying checksum...");
+    let expected = download_checksum(&checksum_url, get_platform_binary_name())?;
+    verify_checksum(&temp_path, &expected)?;
+    println!("   ✓ SHA-256 matches");
+
+    println!("5. Verifying new binary...");
     verify_binary(&temp_path)?;
     println!("   ✓ Verification passed");
 
-    println!("5. Installing new binary...");
+    println!("6. Installing new binary...");
     install_binary(&temp_path)?;
 
-    println!("6. Cleaning up old backups...");
+    println!("7. Cleaning up old backups...");
     cleanup_max_backups(3)?;
 
-    println!("7. Updating state...");
+    println!("8. Updating state...");
     update_state(|state| {
         state.update.last_check = Some(chrono::Utc::now().to_rfc3339());
         state.update.last_notified_version = None;
@@ -0,0 +1,49 @@
+use crate::alias::parse_content;
+use crate::commands::shell_integration::Shell;
+use crate::utils::{read_aliases_sources, record_usage};
+
+pub fn mark_used(alias: &str) -> anyhow::Result<()> {
+    let known = read_aliases_sources()?
+        .iter()
+        .any(|(_, content)| parse_content(content).iter().any(|a| a.name == alias));
+
+    if known {
+        record_usage(alias)?;
+    }
+
+    Ok(())
+}
+
+/// Shell snippet that calls `shorty used` with the first word of every command line.
+pub fn usage_hook(shell: &Shell) -> &'static str {
+    match shell {
+        Shell::Bash => "trap '(shorty used \"${BASH_COMMAND%% *}\" >/dev/null 2>&1 &)' DEBUG\n",
+        Shell::Zsh => concat!(
+            "shorty_preexec() { (shorty used \"${1%% *}\" >/dev/null 2>&1 &) }\n",
+            "autoload -Uz add-zsh-hook && add-zsh-hook preexec shorty_preexec\n",
+        ),
+        Shell::Fish => concat!(
+            "function shorty_preexec --on-event fish_preexec\n",
+            "    shorty used (string split -f1 ' ' -- $argv) >/dev/null 2>&1 &\n",
+            "end\n",
+        ),
+    }
+}
+
+pub fn print_hook(shell: &Shell) {
+    print!("{}", usage_hook(shell));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_shell_hook_calls_shorty_used() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let hook = usage_hook(&shell);
+            assert!(hook.contains("shorty used"), "{:?}: {}", shell, hook);
+            assert!(hook.ends_with('\n'));
+        }
+    }
+}
@@ -1,7 +1,11 @@
+use crate::alias::{parse_entries, parse_function_block, parse_line, shell_quote, to_line};
 use crate::commands::backup::auto_backup;
-use crate::utils::get_aliases_path;
-use std::collections::{HashMap, HashSet};
-use std::fs;
+use crate::utils::acquire_lock;
+use crate::utils::{
+    get_aliases_path, read_aliases_file, read_aliases_sources, write_aliases_atomic,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use which::which;
 
 #[derive(Debug)]
@@ -11,47 +15,56 @@ struct AliasIssue {
     issue_type: IssueType,
     description: String,
     suggestion: Option<String>,
+    duplicate_of: Option<usize>,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
-enum IssueType {
+pub enum IssueType {
     InvalidSyntax,
     CommandNotFound,
     Duplicate,
     SystemConflict,
     EmptyCommand,
     SuspiciousCommand,
+    UnbalancedQuotes,
 }
 
-pub fn validate_aliases(fix_issues: bool) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
+impl std::str::FromStr for IssueType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "invalid-syntax" => Ok(IssueType::InvalidSyntax),
+            "command-not-found" => Ok(IssueType::CommandNotFound),
+            "duplicate" => Ok(IssueType::Duplicate),
+            "system-conflict" => Ok(IssueType::SystemConflict),
+            "empty-command" => Ok(IssueType::EmptyCommand),
+            "suspicious" => Ok(IssueType::SuspiciousCommand),
+            "unbalanced-quotes" => Ok(IssueType::UnbalancedQuotes),
+            _ => anyhow::bail!(
+                "Unknown issue type: {}. Supported: invalid-syntax, command-not-found, duplicate, system-conflict, empty-command, suspicious, unbalanced-quotes",
+                s
+            ),
+        }
+    }
+}
+
+pub fn validate_aliases(fix_issues: bool, only: &[IssueType]) -> anyhow::Result<usize> {
+    let aliases_path = get_aliases_path()?;
 
     if !aliases_path.exists() {
         println!("No aliases file found. Nothing to validate.");
-        return Ok(());
+        return Ok(0);
     }
 
     println!("Validating aliases...\n");
 
-    let content = fs::read_to_string(&aliases_path)?;
-    let mut issues = Vec::new();
-    let mut seen_aliases = HashMap::new();
-
-    for (line_num, line) in content.lines().enumerate() {
-        let line_number = line_num + 1;
-
-        if line.trim().is_empty() || line.trim().starts_with('#') {
-            continue;
-        }
-
-        if let Some(issue) = validate_line(line, line_number, &mut seen_aliases) {
-            issues.push(issue);
-        }
-    }
+    let content = read_aliases_file(&aliases_path)?;
+    let issues = collect_issues(&content, only);
 
     if issues.is_empty() {
         println!("All aliases are valid! No issues found.");
-        return Ok(());
+        return Ok(0);
     }
 
     let mut issues_by_type: HashMap<IssueType, Vec<&AliasIssue>> = HashMap::new();
@@ -81,10 +94,10 @@ pub fn validate_aliases(fix_issues: bool) -> anyhow::Result<()> {
 
     if fix_issues {
         println!("Attempting to fix issues...");
-        auto_backup()?;
-        let fixed_count = fix_aliases(&issues)?;
+        let _lock = acquire_lock()?;
+        let fixed_count = fix_aliases(&content, &issues)?;
         if fixed_count > 0 {
-            println!("Fixed {fixed_count} issue(s).");
+            println!("Fixed {fixed_count} line(s).");
             println!("To apply the changes, please restart your terminal!");
         } else {
             println!("No issues could be automatically fixed.");
@@ -93,38 +106,84 @@ pub fn validate_aliases(fix_issues: bool) -> anyhow::Result<()> {
         println!("Run with --fix to attempt automatic fixes where possible.");
     }
 
-    Ok(())
+    Ok(issues.len())
 }
 
-pub fn check_duplicates(remove_duplicates: bool) -> anyhow::Result<()> {
-    let aliases_path = get_aliases_path();
+fn collect_issues(content: &str, only: &[IssueType]) -> Vec<AliasIssue> {
+    let mut issues = Vec::new();
+    let mut seen_aliases = HashMap::new();
 
-    if !aliases_path.exists() {
-        println!("No aliases file found.");
-        return Ok(());
-    }
+    let lines: Vec<&str> = content.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line_number = i + 1;
+
+        if let Some(block) = parse_function_block(&lines[i..]) {
+            if let Some(issue) =
+                validate_function_block(block.name, line_number, &mut seen_aliases, only)
+            {
+                issues.push(issue);
+            }
+            i += block.line_count;
+            continue;
+        }
 
-    let content = fs::read_to_string(&aliases_path)?;
-    let mut seen_aliases: HashMap<String, Vec<usize>> = HashMap::new();
-    let mut duplicates = Vec::new();
+        let line = lines[i];
+        i += 1;
 
-    for (line_num, line) in content.lines().enumerate() {
-        let line_number = line_num + 1;
+        if line.trim().is_empty() || line.trim().starts_with('#') {
+            continue;
+        }
 
-        if let Some(alias_name) = extract_alias_name(line) {
-            seen_aliases
-                .entry(alias_name.clone())
-                .or_default()
-                .push(line_number);
+        if let Some(issue) = validate_line(line, line_number, &mut seen_aliases, only) {
+            issues.push(issue);
         }
     }
 
-    for (alias_name, line_numbers) in seen_aliases {
-        if line_numbers.len() > 1 {
-            duplicates.push((alias_name, line_numbers));
+    issues
+}
+
+type Occurrences<'a> = Vec<(&'a Path, usize)>;
+
+fn find_duplicates(sources: &[(PathBuf, String)]) -> Vec<(String, Occurrences<'_>)> {
+    let mut seen_aliases: HashMap<String, Occurrences> = HashMap::new();
+
+    for (path, content) in sources {
+        for (line_num, line) in content.lines().enumerate() {
+            if let Some(alias_name) = extract_alias_name(line) {
+                seen_aliases
+                    .entry(alias_name)
+                    .or_default()
+                    .push((path.as_path(), line_num + 1));
+            }
         }
     }
 
+    let mut duplicates: Vec<(String, Occurrences)> = seen_aliases
+        .into_iter()
+        .filter(|(_, occurrences)| occurrences.len() > 1)
+        .collect();
+    duplicates.sort_by(|a, b| a.0.cmp(&b.0));
+    duplicates
+}
+
+pub fn check_duplicates(remove_duplicates: bool, dry_run: bool) -> anyhow::Result<()> {
+    let _lock = if remove_duplicates && !dry_run {
+        Some(acquire_lock()?)
+    } else {
+        None
+    };
+    let aliases_path = get_aliases_path()?;
+    let sources = read_aliases_sources()?;
+
+    if sources.is_empty() {
+        println!("No aliases file found.");
+        return Ok(());
+    }
+
+    let duplicates = find_duplicates(&sources);
+
     if duplicates.is_empty() {
         println!("No duplicate aliases found.");
         return Ok(());
@@ -132,29 +191,47 @@ pub fn check_duplicates(remove_duplicates: bool) -> anyhow::Result<()> {
 
     println!("Found {} duplicate alias(es):", duplicates.len());
 
-    for (alias_name, line_numbers) in &duplicates {
+    let mut cross_file = 0;
+    for (alias_name, occurrences) in &duplicates {
+        let files: HashSet<&Path> = occurrences.iter().map(|(path, _)| *path).collect();
+        if files.len() > 1 {
+            cross_file += 1;
+        }
         println!(
-            "  '{}' appears on lines: {}",
+            "  '{}' appears at: {}",
             alias_name,
-            line_numbers
+            occurrences
                 .iter()
-                .map(|n| n.to_string())
+                .map(|(path, line)| format!("{}:{line}", path.display()))
                 .collect::<Vec<_>>()
                 .join(", ")
         );
     }
 
-    if remove_duplicates {
-        println!("\nRemoving duplicates (keeping the last occurrence)...");
-        auto_backup()?;
+    if remove_duplicates || dry_run {
+        let Some((_, content)) = sources.iter().find(|(path, _)| *path == aliases_path) else {
+            println!(
+                "\nNo aliases file at {}; nothing to remove.",
+                aliases_path.display()
+            );
+            return Ok(());
+        };
+        if cross_file > 0 {
+            println!(
+                "\nNote: {cross_file} alias(es) are duplicated across files; only duplicates within {} are removed.",
+                aliases_path.display()
+            );
+        }
 
         let lines: Vec<String> = content.lines().map(String::from).collect();
         let mut new_lines = Vec::new();
+        let mut removed_lines = Vec::new();
         let mut seen_in_final = HashSet::new();
 
         for (i, line) in lines.iter().enumerate().rev() {
             if let Some(alias_name) = extract_alias_name(line) {
                 if seen_in_final.contains(&alias_name) {
+                    removed_lines.insert(0, (i + 1, line.as_str()));
                     continue;
                 }
                 seen_in_final.insert(alias_name);
@@ -162,15 +239,30 @@ pub fn check_duplicates(remove_duplicates: bool) -> anyhow::Result<()> {
             new_lines.insert(0, (i, line.clone()));
         }
 
+        if dry_run {
+            println!(
+                "\nWould remove {} line(s) (keeping the last occurrence):",
+                removed_lines.len()
+            );
+            for (line_number, line) in &removed_lines {
+                println!("  {line_number}: {line}");
+            }
+            println!("Dry run: no changes were made.");
+            return Ok(());
+        }
+
+        println!("\nRemoving duplicates (keeping the last occurrence)...");
+        auto_backup()?;
+
         let final_content = new_lines
             .iter()
             .map(|(_, line)| line.as_str())
             .collect::<Vec<_>>()
             .join("\n");
         if !final_content.is_empty() && !final_content.ends_with('\n') {
-            fs::write(&aliases_path, format!("{final_content}\n"))?;
+            write_aliases_atomic(&format!("{final_content}\n"))?;
         } else {
-            fs::write(&aliases_path, final_content)?;
+            write_aliases_atomic(&final_content)?;
         }
 
         let removed_count = lines.len() - new_lines.len();
@@ -183,103 +275,180 @@ pub fn check_duplicates(remove_duplicates: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
+pub(crate) fn line_warning(line: &str, seen_aliases: &HashMap<String, usize>) -> Option<String> {
+    let issue = validate_line(line, 0, &mut seen_aliases.clone(), &[])?;
+    Some(match issue.suggestion {
+        Some(suggestion) => format!("{} ({suggestion})", issue.description),
+        None => issue.description,
+    })
+}
+
 fn validate_line(
     line: &str,
     line_number: usize,
     seen_aliases: &mut HashMap<String, usize>,
+    only: &[IssueType],
 ) -> Option<AliasIssue> {
     let line = line.trim();
+    let check_enabled = |issue_type: IssueType| only.is_empty() || only.contains(&issue_type);
 
     if !line.starts_with("alias ") {
+        if !check_enabled(IssueType::InvalidSyntax) {
+            return None;
+        }
         return Some(AliasIssue {
             line_number,
             alias_name: "unknown".to_string(),
             issue_type: IssueType::InvalidSyntax,
             description: "Line doesn't start with 'alias'".to_string(),
             suggestion: Some("Ensure line starts with 'alias name=command'".to_string()),
+            duplicate_of: None,
         });
     }
 
     if let Some(eq_pos) = line.find('=') {
         let alias_part = &line[6..eq_pos].trim();
-        let command_part = &line[eq_pos + 1..];
 
         if alias_part.is_empty() {
+            if !check_enabled(IssueType::InvalidSyntax) {
+                return None;
+            }
             return Some(AliasIssue {
                 line_number,
                 alias_name: "empty".to_string(),
                 issue_type: IssueType::InvalidSyntax,
                 description: "Empty alias name".to_string(),
                 suggestion: Some("Provide a valid alias name".to_string()),
+                duplicate_of: None,
             });
         }
 
-        if let Some(&previous_line) = seen_aliases.get(&alias_part.to_string()) {
+        if let Some(&previous_line) = seen_aliases
+            .get(&alias_part.to_string())
+            .filter(|_| check_enabled(IssueType::Duplicate))
+        {
             return Some(AliasIssue {
                 line_number,
                 alias_name: alias_part.to_string(),
                 issue_type: IssueType::Duplicate,
                 description: format!("Duplicate of alias on line {previous_line}"),
                 suggestion: Some("Remove one of the duplicate aliases".to_string()),
+                duplicate_of: Some(previous_line),
             });
         }
         seen_aliases.insert(alias_part.to_string(), line_number);
 
-        let command = extract_command(command_part);
+        if let Some(problem) = quoting_problem(line[eq_pos + 1..].trim()) {
+            let (issue_type, suggestion) = match problem {
+                QuotingProblem::Unbalanced => (
+                    IssueType::UnbalancedQuotes,
+                    "Close the open quote or escape it with a backslash",
+                ),
+                QuotingProblem::Unquoted => (
+                    IssueType::InvalidSyntax,
+                    "Wrap the command in single quotes",
+                ),
+            };
+            if check_enabled(issue_type.clone()) {
+                return Some(AliasIssue {
+                    line_number,
+                    alias_name: alias_part.to_string(),
+                    issue_type,
+                    description: problem.description().to_string(),
+                    suggestion: Some(suggestion.to_string()),
+                    duplicate_of: None,
+                });
+            }
+        }
+
+        let command = parse_line(line)
+            .map(|alias| alias.command)
+            .unwrap_or_default();
 
         if command.is_empty() {
+            if !check_enabled(IssueType::EmptyCommand) {
+                return None;
+            }
             return Some(AliasIssue {
                 line_number,
                 alias_name: alias_part.to_string(),
                 issue_type: IssueType::EmptyCommand,
                 description: "Empty command".to_string(),
                 suggestion: Some("Provide a valid command".to_string()),
+                duplicate_of: None,
             });
         }
 
         let first_word = command.split_whitespace().next().unwrap_or("");
         if !first_word.is_empty() && !command_exists(first_word) {
-            if is_system_command(alias_part) {
+            if is_system_command(alias_part) && check_enabled(IssueType::SystemConflict) {
                 return Some(AliasIssue {
                     line_number,
                     alias_name: alias_part.to_string(),
                     issue_type: IssueType::SystemConflict,
                     description: format!("Conflicts with system command '{alias_part}'"),
                     suggestion: Some("Consider using a different alias name".to_string()),
+                    duplicate_of: None,
                 });
             }
 
-            return Some(AliasIssue {
-                line_number,
-                alias_name: alias_part.to_string(),
-                issue_type: IssueType::CommandNotFound,
-                description: format!("Command '{first_word}' not found in PATH"),
-                suggestion: Some("Check if command is installed or fix typo".to_string()),
-            });
+            if check_enabled(IssueType::CommandNotFound) {
+                return Some(AliasIssue {
+                    line_number,
+                    alias_name: alias_part.to_string(),
+                    issue_type: IssueType::CommandNotFound,
+                    description: format!("Command '{first_word}' not found in PATH"),
+                    suggestion: Some("Check if command is installed or fix typo".to_string()),
+                    duplicate_of: None,
+                });
+            }
         }
 
-        if is_suspicious_command(&command) {
+        if check_enabled(IssueType::SuspiciousCommand) && is_suspicious_command(&command) {
             return Some(AliasIssue {
                 line_number,
                 alias_name: alias_part.to_string(),
                 issue_type: IssueType::SuspiciousCommand,
                 description: "Potentially dangerous command detected".to_string(),
                 suggestion: Some("Review this alias carefully".to_string()),
+                duplicate_of: None,
             });
         }
-    } else {
+    } else if check_enabled(IssueType::InvalidSyntax) {
         return Some(AliasIssue {
             line_number,
             alias_name: "unknown".to_string(),
             issue_type: IssueType::InvalidSyntax,
             description: "Missing '=' in alias definition".to_string(),
             suggestion: Some("Use format: alias name=command".to_string()),
+            duplicate_of: None,
         });
     }
 
     None
 }
 
+fn validate_function_block(
+    name: &str,
+    line_number: usize,
+    seen_aliases: &mut HashMap<String, usize>,
+    only: &[IssueType],
+) -> Option<AliasIssue> {
+    let previous_line = seen_aliases.insert(name.to_string(), line_number)?;
+    if !only.is_empty() && !only.contains(&IssueType::Duplicate) {
+        return None;
+    }
+
+    Some(AliasIssue {
+        line_number,
+        alias_name: name.to_string(),
+        issue_type: IssueType::Duplicate,
+        description: format!("Duplicate of alias on line {previous_line}"),
+        suggestion: Some("Remove one of the duplicate aliases".to_string()),
+        duplicate_of: Some(previous_line),
+    })
+}
+
 fn extract_alias_name(line: &str) -> Option<String> {
     let line = line.trim();
     if !line.starts_with("alias ") {
@@ -296,48 +465,12 @@ fn extract_alias_name(line: &str) -> Option<String> {
     None
 }
 
-fn extract_command(command_part: &str) -> String {
-    let command_part = command_part.trim();
-
-    let mut command_end = command_part.len();
-    let mut in_quotes = false;
-    let mut quote_char = ' ';
-    let mut i = 0;
-
-    while i < command_part.len() {
-        let ch = command_part.chars().nth(i).unwrap();
-        match ch {
-            '\'' | '"' if !in_quotes => {
-                in_quotes = true;
-                quote_char = ch;
-            }
-            c if in_quotes && c == quote_char => {
-                in_quotes = false;
-            }
-            '#' if !in_quotes => {
-                command_end = i;
-                break;
-            }
-            _ => {}
-        }
-        i += 1;
-    }
-
-    let mut command = command_part[..command_end].trim();
-
-    if (command.starts_with('\'') && command.ends_with('\''))
-        || (command.starts_with('"') && command.ends_with('"'))
-    {
-        command = &command[1..command.len() - 1];
-    }
-
-    command.to_string()
-}
-
-fn command_exists(command: &str) -> bool {
+pub(crate) fn command_exists(command: &str) -> bool {
     let builtins = [
         "cd", "echo", "pwd", "exit", "source", ".", "alias", "unalias", "export", "set", "unset",
-        "history", "jobs", "bg", "fg", "kill",
+        "history", "jobs", "bg", "fg", "kill", "builtin", "command", "eval", "exec", "type",
+        "read", "printf", "test", "[", "true", "false", "pushd", "popd", "dirs", "trap", "umask",
+        "wait", "hash", "local", "return", "shift", "declare", "typeset", "disown", "ulimit",
     ];
 
     if builtins.contains(&command) {
@@ -380,14 +513,328 @@ fn format_issue_type(issue_type: &IssueType) -> &str {
         IssueType::SystemConflict => "System Command Conflicts",
         IssueType::EmptyCommand => "Empty Commands",
         IssueType::SuspiciousCommand => "Suspicious Commands",
+        IssueType::UnbalancedQuotes => "Unbalanced Quotes",
     }
 }
 
-fn fix_aliases(issues: &[AliasIssue]) -> anyhow::Result<usize> {
-    let _fixable_count = issues
-        .iter()
-        .filter(|issue| matches!(issue.issue_type, IssueType::Duplicate))
-        .count();
+#[derive(Debug, PartialEq)]
+enum QuotingProblem {
+    Unbalanced,
+    Unquoted,
+}
+
+impl QuotingProblem {
+    fn description(&self) -> &'static str {
+        match self {
+            QuotingProblem::Unbalanced => "Unbalanced quotes in command",
+            QuotingProblem::Unquoted => "Command is not quoted",
+        }
+    }
+}
+
+fn quoting_problem(value: &str) -> Option<QuotingProblem> {
+    if has_unbalanced_quotes(value) {
+        return Some(QuotingProblem::Unbalanced);
+    }
+    if value.starts_with('\'') || value.starts_with('"') {
+        return None;
+    }
+
+    let command = value.split('#').next().unwrap_or("").trim();
+    command
+        .contains(char::is_whitespace)
+        .then_some(QuotingProblem::Unquoted)
+}
+
+fn has_unbalanced_quotes(value: &str) -> bool {
+    let mut quote: Option<char> = None;
+    let mut word_start = true;
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => {}
+            (Some(_), '\\') | (None, '\\') => {
+                chars.next();
+            }
+            (Some(_), '"') => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '#') if word_start => break,
+            (None, _) => {}
+        }
+        word_start = quote.is_none() && c.is_whitespace();
+    }
+
+    quote.is_some()
+}
+
+fn requote_line(line: &str) -> Option<String> {
+    let line = line.trim();
+    let (name, value) = line.strip_prefix("alias ")?.split_once('=')?;
+    let value = value.trim();
+
+    match quoting_problem(value)? {
+        QuotingProblem::Unbalanced => {
+            let body = value.strip_prefix(['\'', '"'])?;
+            let (command, comment) = match body.find(" #") {
+                Some(pos) => (&body[..pos], &body[pos..]),
+                None => (body, ""),
+            };
+            let command = command.trim();
+            if command.is_empty() {
+                return None;
+            }
+            Some(format!(
+                "alias {}={}{comment}",
+                name.trim(),
+                shell_quote(command)
+            ))
+        }
+        QuotingProblem::Unquoted => parse_line(line).map(|alias| to_line(&alias)),
+    }
+}
+
+fn fix_aliases(content: &str, issues: &[AliasIssue]) -> anyhow::Result<usize> {
+    let (new_content, fixed_count) = plan_fixes(content, issues);
+    if fixed_count == 0 {
+        return Ok(0);
+    }
+
+    auto_backup()?;
+    write_aliases_atomic(&new_content)?;
+
+    Ok(fixed_count)
+}
+
+/// Applies the automatic fixes to `content`, returning the new content and the
+/// number of lines that were removed or rewritten.
+fn plan_fixes(content: &str, issues: &[AliasIssue]) -> (String, usize) {
+    let block_sizes: HashMap<usize, usize> = parse_entries(content)
+        .into_iter()
+        .map(|entry| (entry.line_number, entry.line_count))
+        .collect();
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut definitions: HashMap<&str, BTreeSet<usize>> = HashMap::new();
+    for issue in issues {
+        if let Some(previous_line) = issue.duplicate_of {
+            let lines = definitions.entry(issue.alias_name.as_str()).or_default();
+            lines.extend([previous_line, issue.line_number]);
+        }
+    }
 
-    Ok(0)
+    let mut removed: HashSet<usize> = HashSet::new();
+    for definition_lines in definitions.values() {
+        if let Some(&last) = definition_lines.last() {
+            removed.extend(definition_lines.range(..last));
+        }
+    }
+
+    let mut replaced: HashMap<usize, String> = HashMap::new();
+
+    for issue in issues {
+        let line = issue.line_number;
+        let outcome = match issue.issue_type {
+            IssueType::Duplicate => {
+                let definition_lines = &definitions[issue.alias_name.as_str()];
+                let last = definition_lines.last().copied().unwrap_or(line);
+                if line == last {
+                    let earlier: Vec<String> = definition_lines
+                        .range(..last)
+                        .map(|l| l.to_string())
+                        .collect();
+                    println!(
+                        "  Kept line {line}: {} - last definition, removed line(s) {}",
+                        issue.alias_name,
+                        earlier.join(", ")
+                    );
+                    continue;
+                } else {
+                    Some(format!(
+                        "removed in favour of the definition on line {last}"
+                    ))
+                }
+            }
+            IssueType::EmptyCommand if !removed.contains(&line) => {
+                removed.insert(line);
+                Some("removed alias with empty command".to_string())
+            }
+            IssueType::InvalidSyntax if !removed.contains(&line) => lines
+                .get(line - 1)
+                .and_then(|text| requote_line(text))
+                .map(|new_line| {
+                    replaced.insert(line, new_line);
+                    "quoted command".to_string()
+                }),
+            IssueType::UnbalancedQuotes if !removed.contains(&line) => {
+                lines.get(line - 1).map(|text| match requote_line(text) {
+                    Some(new_line) => {
+                        replaced.insert(line, new_line);
+                        "balanced quotes".to_string()
+                    }
+                    None => {
+                        replaced.insert(line, format!("# {text}"));
+                        "commented out line with unbalanced quotes".to_string()
+                    }
+                })
+            }
+            _ => None,
+        };
+
+        match outcome {
+            Some(action) => {
+                println!("  Fixed line {line}: {} - {action}", issue.alias_name);
+            }
+            None => println!(
+                "  Skipped line {line}: {} - {} cannot be fixed automatically",
+                issue.alias_name,
+                format_issue_type(&issue.issue_type)
+            ),
+        }
+    }
+
+    let fixed_count = removed.len() + replaced.keys().filter(|l| !removed.contains(l)).count();
+    if fixed_count == 0 {
+        return (content.to_string(), 0);
+    }
+
+    let mut new_lines = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line_number = i + 1;
+        if removed.contains(&line_number) {
+            i += block_sizes.get(&line_number).copied().unwrap_or(1);
+            continue;
+        }
+        match replaced.get(&line_number) {
+            Some(new_line) => new_lines.push(new_line.as_str()),
+            None => new_lines.push(lines[i]),
+        }
+        i += 1;
+    }
+
+    let mut new_content = new_lines.join("\n");
+    if content.ends_with('\n') {
+        new_content.push('\n');
+    }
+
+    (new_content, fixed_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duplicate_issues(content: &str) -> Vec<AliasIssue> {
+        let mut seen = HashMap::new();
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| validate_line(line, i + 1, &mut seen, &[IssueType::Duplicate]))
+            .collect()
+    }
+
+    #[test]
+    fn mixed_quote_commands_are_extracted_whole() {
+        let cases = [
+            (
+                r#"alias gl='git log --format="%h %s"' # short log"#,
+                r#"git log --format="%h %s""#,
+            ),
+            (r#"alias say="echo 'hi # there'""#, "echo 'hi # there'"),
+            (r"alias its='echo it'\''s'", "echo it's"),
+        ];
+
+        for (line, command) in cases {
+            assert_eq!(
+                parse_line(line).map(|a| a.command).as_deref(),
+                Some(command)
+            );
+
+            let only = [
+                IssueType::InvalidSyntax,
+                IssueType::EmptyCommand,
+                IssueType::UnbalancedQuotes,
+            ];
+            let issue = validate_line(line, 1, &mut HashMap::new(), &only);
+            assert!(issue.is_none(), "{line}: {issue:?}");
+        }
+    }
+
+    #[test]
+    fn missing_closing_quote_is_reported_and_fixed() {
+        let line = "alias gs='git status";
+        let issue = validate_line(line, 1, &mut HashMap::new(), &[]).unwrap();
+        assert_eq!(issue.issue_type, IssueType::UnbalancedQuotes);
+
+        let (new_content, fixed_count) = plan_fixes(&format!("{line}\n"), &[issue]);
+        assert_eq!(new_content, "alias gs='git status'\n");
+        assert_eq!(fixed_count, 1);
+
+        let line = r#"alias say="echo hi # greet"#;
+        let issue = validate_line(line, 1, &mut HashMap::new(), &[]).unwrap();
+        assert_eq!(issue.issue_type, IssueType::UnbalancedQuotes);
+    }
+
+    #[test]
+    fn duplicate_fix_counts_only_removed_lines() {
+        let content = "alias a='one'\nalias a='two'\nalias a='three'\nalias b='ok'\n";
+        let issues = duplicate_issues(content);
+        assert_eq!(issues.len(), 2);
+
+        let (new_content, fixed_count) = plan_fixes(content, &issues);
+        assert_eq!(new_content, "alias a='three'\nalias b='ok'\n");
+        assert_eq!(fixed_count, 2);
+    }
+
+    #[test]
+    fn single_duplicate_fix_counts_one_line() {
+        let content = "alias a='one'\nalias a='two'\n";
+        let (new_content, fixed_count) = plan_fixes(content, &duplicate_issues(content));
+        assert_eq!(new_content, "alias a='two'\n");
+        assert_eq!(fixed_count, 1);
+    }
+
+    #[test]
+    fn only_filter_reports_just_the_requested_issue_type() {
+        let content = "alias wipe='rm -rf /tmp/build'\n\
+                       alias gone='no-such-command-xyz'\n\
+                       alias wipe='echo again'\n\
+                       not an alias\n";
+
+        let issues = collect_issues(content, &[IssueType::SuspiciousCommand]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::SuspiciousCommand);
+        assert_eq!(issues[0].line_number, 1);
+        assert!(collect_issues(content, &[]).len() > 1);
+    }
+
+    #[test]
+    fn duplicates_across_merged_files_report_both_sources() {
+        let primary = PathBuf::from("/home/me/.shorty/aliases");
+        let work = PathBuf::from("/home/me/work-aliases");
+        let sources = [
+            (
+                primary.clone(),
+                "alias gs='git status'\nalias ll='ls -la'\n".to_string(),
+            ),
+            (
+                work.clone(),
+                "alias kc='kubectl'\nalias gs='git status -sb'\n".to_string(),
+            ),
+        ];
+
+        let duplicates = find_duplicates(&sources);
+
+        assert_eq!(
+            duplicates,
+            [(
+                "gs".to_string(),
+                vec![(primary.as_path(), 1), (work.as_path(), 2)]
+            )]
+        );
+    }
 }
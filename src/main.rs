@@ -1,3 +1,4 @@
+pub mod alias;
 pub mod utils;
 pub mod updater;
 
@@ -6,28 +7,43 @@ mod commands {
     pub mod backup;
     pub mod categories;
     pub mod config;
+    pub mod doctor;
     pub mod edit;
+    pub mod history;
     pub mod import_export;
     pub mod interactive;
     pub mod list;
+    pub mod lookup;
     pub mod plugins;
     pub mod remove;
+    pub mod rename;
+    pub mod repair;
     pub mod search;
     pub mod shell_integration;
     pub mod stats;
     pub mod sync;
+    pub mod tags;
     pub mod templates;
     pub mod uninstall;
     pub mod update;
+    pub mod usage;
     pub mod validate;
 }
 
 use clap::{Parser, Subcommand};
+use std::io::IsTerminal;
+use std::process::ExitCode;
+use std::time::Instant;
+use utils::OutputFormat;
 
 #[derive(Parser)]
 #[command(name = "shorty")]
 #[command(about = "Manage your shell aliases", version = env!("CARGO_PKG_VERSION"))]
 struct Cli {
+    #[arg(long, global = true, help = "Print how long the command took to stderr")]
+    timing: bool,
+    #[arg(long, global = true, help = "Print structured JSON instead of text (read-only commands)")]
+    json: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -41,6 +57,16 @@ enum Commands {
         note: Option<String>,
         #[arg(short, long, num_args = 1.., use_value_delimiter = true, help = "Add tags to the alias")]
         tags: Vec<String>,
+        #[arg(long, alias = "tag-from-command", help = "Automatically tag the alias based on its command")]
+        auto_tag: bool,
+        #[arg(long, help = "Store the command as a shell function, turning literal \\n into line breaks")]
+        multiline: bool,
+        #[arg(long, help = "Overwrite an existing alias without asking if its definition differs")]
+        overwrite_if_different: bool,
+        #[arg(long, conflicts_with = "after", help = "Insert the alias just before this existing alias")]
+        before: Option<String>,
+        #[arg(long, help = "Insert the alias just after this existing alias")]
+        after: Option<String>,
     },
     Edit {
         alias: String,
@@ -53,16 +79,42 @@ enum Commands {
     List {
         #[arg(short, long, help = "Filter aliases by tag")]
         tag: Option<String>,
+        #[arg(long, help = "Show the source file of each alias")]
+        sources: bool,
+        #[arg(long, default_value = "name", help = "Sort by (name, command, recent, used)")]
+        sort: String,
+        #[arg(long, help = "Reverse the sort order")]
+        reverse: bool,
+        #[arg(long, help = "Print only alias names, one per line")]
+        names_only: bool,
+        #[arg(long, value_delimiter = ',', help = "Columns to show, in order (name, command, note, tags, source)")]
+        columns: Vec<String>,
     },
     Remove {
-        alias: String,
+        #[arg(required_unless_present = "tag", help = "Alias names or glob patterns (e.g. 'g*')")]
+        aliases: Vec<String>,
+        #[arg(long, help = "Remove every alias with this tag")]
+        tag: Option<String>,
+        #[arg(long, help = "Show what would be removed without changing the file")]
+        dry_run: bool,
+    },
+    Rename {
+        old: String,
+        new: String,
+        #[arg(long, help = "Allow a new name that shadows a system command")]
+        force: bool,
     },
     Search {
-        keyword: String,
-        #[arg(long, help = "Search in specific field (command, note, tag)")]
+        #[arg(required = true, num_args = 1..)]
+        keywords: Vec<String>,
+        #[arg(long = "match", default_value = "any", help = "Require all or any of the keywords to match (all, any)")]
+        match_mode: String,
+        #[arg(long, help = "Search in specific field (name, command, note, tag)")]
         r#in: Option<String>,
         #[arg(long, help = "Use regex pattern matching")]
         regex: bool,
+        #[arg(long, help = "Show the name, command, note and tags of each match")]
+        context: bool,
     },
     Backup {
         #[command(subcommand)]
@@ -71,10 +123,33 @@ enum Commands {
     Validate {
         #[arg(long, help = "Automatically fix issues where possible")]
         fix: bool,
+        #[arg(long, value_delimiter = ',', help = "Only run these checks (invalid-syntax, command-not-found, duplicate, system-conflict, empty-command, suspicious, unbalanced-quotes)")]
+        only: Vec<String>,
+        #[arg(long, help = "Exit with a non-zero status when any issue is found")]
+        strict: bool,
+    },
+    Used {
+        #[arg(
+            required_unless_present = "hook",
+            help = "Alias that was just run (ignored if it is not a known alias)"
+        )]
+        alias: Option<String>,
+        #[arg(long, conflicts_with = "alias", help = "Print a shell hook (bash, zsh, fish) that records usage automatically")]
+        hook: Option<String>,
+    },
+    Doctor {
+        #[arg(long, help = "Repair the problems that are found")]
+        fix: bool,
+    },
+    Repair {
+        #[arg(long, help = "Drop lines that are not valid UTF-8 from the aliases file")]
+        encoding: bool,
     },
     Duplicates {
         #[arg(long, help = "Remove duplicate aliases")]
         remove: bool,
+        #[arg(long, help = "Show the lines that would be removed without changing the file")]
+        dry_run: bool,
     },
     #[command(alias = "i")]
     Interactive,
@@ -82,20 +157,43 @@ enum Commands {
         #[command(subcommand)]
         action: ConfigAction,
     },
-    Stats,
+    Stats {
+        #[arg(long, help = "Re-render the report on an interval until q or Ctrl+C")]
+        watch: bool,
+        #[arg(long, default_value = "2", requires = "watch", help = "Seconds between refreshes in --watch mode")]
+        interval: u64,
+        #[arg(long, conflicts_with = "watch", help = "Deprecated: use --json")]
+        format: Option<String>,
+        #[arg(long, conflicts_with = "watch", help = "Show a histogram of command lengths")]
+        by_length: bool,
+    },
     Export {
-        #[arg(long, default_value = "json", help = "Export format (json, csv, bash)")]
+        #[arg(long, default_value = "json", help = "Export format (json, csv, bash, toml, yaml)")]
         format: String,
         #[arg(short, long, help = "Output file path")]
         output: Option<String>,
+        #[arg(long, help = "Directory for the auto-named export file")]
+        output_dir: Option<String>,
+        #[arg(long, conflicts_with = "compact", help = "Pretty-print JSON/YAML output (default)")]
+        pretty: bool,
+        #[arg(long, help = "Write minified JSON/YAML output")]
+        compact: bool,
+        #[arg(long, help = "Sort aliases by name and omit timestamps for stable, diff-friendly output")]
+        sort: bool,
     },
     Import {
-        #[arg(help = "Source to import from (file path, bash, zsh, fish)")]
+        #[arg(help = "Source to import from (file path, bash, zsh, fish, live)")]
         source: String,
-        #[arg(long, help = "Source format (json, csv, bash)")]
+        #[arg(long, help = "Source format (json, csv, bash, env, toml, yaml)")]
         format: Option<String>,
         #[arg(long, help = "Preview import without making changes")]
         dry_run: bool,
+        #[arg(long, help = "How to handle name conflicts (skip, overwrite, rename)")]
+        on_conflict: Option<String>,
+        #[arg(long, help = "Prefix added to every imported alias name")]
+        rename_prefix: Option<String>,
+        #[arg(long, value_delimiter = ',', help = "Tags added to every imported alias")]
+        tags: Vec<String>,
     },
     Template {
         #[command(subcommand)]
@@ -105,9 +203,21 @@ enum Commands {
         #[command(subcommand)]
         action: CategoryAction,
     },
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+    Tags {
+        #[arg(long, default_value = "count", help = "Sort order (count, name)")]
+        sort: String,
+    },
     Completion {
         #[arg(long, help = "Target shell (bash, zsh, fish)")]
         shell: String,
+        #[arg(long, help = "Directory to write the completion script to")]
+        output_dir: Option<String>,
+        #[arg(long, help = "Generate completions for your alias names instead of shorty itself")]
+        aliases: bool,
     },
     Sync {
         #[command(subcommand)]
@@ -121,18 +231,50 @@ enum Commands {
             help = "Sharing method (clipboard, qr, file)"
         )]
         method: String,
+        #[arg(short, long, help = "PNG file to write the QR code to (qr method)")]
+        output: Option<String>,
+        #[arg(long, help = "Directory to write shared files to")]
+        output_dir: Option<String>,
+        #[arg(long, help = "Shell syntax of the shared file (bash, zsh, fish, nu, powershell)")]
+        format: Option<String>,
     },
     Plugin {
         #[command(subcommand)]
         action: PluginAction,
     },
     Uninstall,
+    #[command(name = "__clipboard", hide = true)]
+    ServeClipboard,
     Update {
         #[arg(long, help = "Only check for updates without installing")]
         check: bool,
         #[arg(long, help = "Force reinstall current version")]
         force: bool,
     },
+    History {
+        #[arg(short, long, default_value = "20", help = "Number of entries to show")]
+        limit: usize,
+    },
+    Alias {
+        #[command(subcommand)]
+        action: AliasAction,
+    },
+    Get {
+        alias: String,
+        #[arg(short, long, help = "Print only the command, or nothing if the alias is missing")]
+        quiet: bool,
+        #[arg(long, help = "Deprecated: use --json")]
+        format: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AliasAction {
+    Exists {
+        alias: String,
+        #[arg(long, help = "Print the alias command when it exists")]
+        print: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -143,8 +285,15 @@ enum BackupAction {
     },
     Restore {
         backup_file: String,
+        #[arg(long, help = "Restore into this file instead of the active aliases file")]
+        into: Option<String>,
     },
     List,
+    Compare {
+        backup_file: String,
+        #[arg(help = "Second backup to compare against (default: the active aliases file)")]
+        other: Option<String>,
+    },
     Clean {
         #[arg(long, default_value = "30", help = "Remove backups older than N days")]
         older_than: u32,
@@ -157,6 +306,7 @@ enum ConfigAction {
     Get { key: String },
     List,
     Reset,
+    Validate,
 }
 
 #[derive(Subcommand)]
@@ -179,6 +329,14 @@ enum TemplateAction {
         params: Option<String>,
         #[arg(short, long, help = "Custom alias name")]
         alias_name: Option<String>,
+        #[arg(long, help = "Print the generated command instead of creating an alias")]
+        stdout: bool,
+        #[arg(long, conflicts_with = "stdout", help = "Print the generated alias definition as a shell snippet")]
+        snippet: bool,
+        #[arg(short, long, conflicts_with_all = ["stdout", "snippet"], help = "Append the alias to this file instead of the main aliases file")]
+        output: Option<String>,
+        #[arg(long, help = "Fill every missing parameter from its default value")]
+        all_defaults: bool,
     },
     Remove {
         name: String,
@@ -215,6 +373,8 @@ enum CategoryAction {
         tree: bool,
         #[arg(long, help = "Show alias counts")]
         counts: bool,
+        #[arg(long, help = "Sort by (name, count, created)")]
+        sort: Option<String>,
     },
     Remove {
         name: String,
@@ -227,10 +387,32 @@ enum CategoryAction {
     },
     Show {
         name: String,
+        #[arg(long, help = "Also count aliases in all child categories")]
+        recursive: bool,
     },
     Group,
 }
 
+#[derive(Subcommand)]
+enum TagAction {
+    Rename {
+        old: String,
+        new: String,
+    },
+    Delete {
+        name: String,
+    },
+    Merge {
+        first: String,
+        second: String,
+        into: String,
+    },
+    List {
+        #[arg(long, default_value = "count", help = "Sort order (count, name)")]
+        sort: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum SyncAction {
     Init {
@@ -239,9 +421,25 @@ enum SyncAction {
         #[arg(long, help = "Git branch name")]
         branch: Option<String>,
     },
-    Push,
+    Clone {
+        url: String,
+        #[arg(long, help = "Git branch name (default: the remote's default branch)")]
+        branch: Option<String>,
+        #[arg(long, help = "Replace an existing sync directory")]
+        force: bool,
+    },
+    Push {
+        #[arg(long, help = "Overwrite the remote branch with local aliases (uses --force-with-lease)")]
+        force: bool,
+        #[arg(short, long, help = "Skip the confirmation prompt for --force")]
+        yes: bool,
+    },
     Pull,
     Status,
+    Diff {
+        #[arg(long, help = "Fetch and compare against the remote branch instead of the last synced copy")]
+        remote: bool,
+    },
     Remote {
         #[command(subcommand)]
         action: RemoteAction,
@@ -274,6 +472,8 @@ enum PluginAction {
     },
     Enable {
         name: String,
+        #[arg(long, help = "Enable even if the plugin's dependencies are missing")]
+        skip_deps: bool,
     },
     Disable {
         name: String,
@@ -281,31 +481,84 @@ enum PluginAction {
     Show {
         name: String,
     },
+    Doctor,
     Run {
         plugin: String,
         command: String,
+        #[arg(long, help = "Kill the plugin command after this many seconds (0 = no limit)")]
+        timeout: Option<u64>,
         #[arg(trailing_var_arg = true, help = "Plugin command arguments")]
         args: Vec<String>,
     },
 }
 
-fn main() -> anyhow::Result<()> {
+fn main() -> anyhow::Result<ExitCode> {
+    let cli = Cli::parse();
+
+    let started = Instant::now();
+    let output = if cli.json { OutputFormat::Json } else { OutputFormat::Text };
+    let result = run_command(&cli.command, output);
+    if cli.timing {
+        eprintln!("Completed in {:.3}s", started.elapsed().as_secs_f64());
+    }
+
+    if result.is_ok() && output == OutputFormat::Text {
+        check_for_updates(&cli.command);
+    }
+
+    result
+}
+
+fn legacy_format(format: Option<&str>, output: OutputFormat) -> anyhow::Result<OutputFormat> {
+    match format {
+        Some(format) => {
+            eprintln!("Warning: --format is deprecated, use the global --json flag instead");
+            let format = format.parse()?;
+            Ok(if output == OutputFormat::Json { OutputFormat::Json } else { format })
+        }
+        None => Ok(output),
+    }
+}
+
+fn check_for_updates(command: &Commands) {
+    if matches!(command, Commands::Update { .. } | Commands::ServeClipboard)
+        || !std::io::stdout().is_terminal()
+    {
+        return;
+    }
+
     if let Ok(config) = commands::config::Config::load() {
         if config.update.enabled {
             let _ = updater::checker::check_for_updates_background(config.update.check_interval_hours);
         }
     }
+}
 
-    let cli = Cli::parse();
-
-    match &cli.command {
+fn run_command(command: &Commands, output: OutputFormat) -> anyhow::Result<ExitCode> {
+    match command {
         Commands::Add {
             alias,
             command,
             note,
             tags,
+            auto_tag,
+            multiline,
+            overwrite_if_different,
+            before,
+            after,
         } => {
-            commands::add::add_alias(alias, command, note, tags)?;
+            let placement = match (before, after) {
+                (Some(target), _) => commands::add::Placement::Before(target.clone()),
+                (None, Some(target)) => commands::add::Placement::After(target.clone()),
+                (None, None) => commands::add::Placement::End,
+            };
+            let options = commands::add::AddOptions {
+                auto_tag: *auto_tag,
+                multiline: *multiline,
+                overwrite_if_different: *overwrite_if_different,
+                placement,
+            };
+            commands::add::add_alias(alias, command, note, tags, &options)?;
         }
         Commands::Edit {
             alias,
@@ -315,38 +568,104 @@ fn main() -> anyhow::Result<()> {
         } => {
             commands::edit::edit_alias(alias, new_command, note, tags)?;
         }
-        Commands::List { tag } => {
-            commands::list::list_aliases(tag.as_deref())?;
+        Commands::List {
+            tag,
+            sources,
+            sort,
+            reverse,
+            names_only,
+            columns,
+        } => {
+            let sort = sort.parse()?;
+            let columns = columns
+                .iter()
+                .map(|c| c.parse())
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            commands::list::list_aliases(
+                tag.as_deref(),
+                *sources,
+                sort,
+                *reverse,
+                *names_only,
+                &columns,
+                output,
+            )?;
         }
-        Commands::Remove { alias } => {
-            commands::remove::remove_alias(alias)?;
+        Commands::Remove {
+            aliases,
+            tag,
+            dry_run,
+        } => match aliases.as_slice() {
+            [alias] if tag.is_none() && !alias.contains(['*', '?']) => {
+                commands::remove::remove_alias(alias, *dry_run)?;
+            }
+            _ => commands::remove::remove_aliases(aliases, tag.as_deref(), *dry_run)?,
+        },
+        Commands::Rename { old, new, force } => {
+            commands::rename::rename_alias(old, new, *force)?;
         }
         Commands::Search {
-            keyword,
+            keywords,
+            match_mode,
             r#in,
             regex,
+            context,
         } => {
-            commands::search::search_aliases(keyword, r#in.as_deref(), *regex)?;
+            let match_mode = match_mode.parse()?;
+            commands::search::search_aliases(
+                keywords,
+                match_mode,
+                r#in.as_deref(),
+                *regex,
+                *context,
+                output,
+            )?;
         }
         Commands::Backup { action } => match action {
             BackupAction::Create { name } => {
                 commands::backup::create_backup(name.as_deref())?;
             }
-            BackupAction::Restore { backup_file } => {
-                commands::backup::restore_backup(backup_file)?;
+            BackupAction::Restore { backup_file, into } => {
+                commands::backup::restore_backup(backup_file, into.as_deref())?;
             }
             BackupAction::List => {
-                commands::backup::list_backups()?;
+                commands::backup::list_backups(output)?;
+            }
+            BackupAction::Compare { backup_file, other } => {
+                commands::backup::compare_backups(backup_file, other.as_deref())?;
             }
             BackupAction::Clean { older_than } => {
                 commands::backup::clean_backups(*older_than)?;
             }
         },
-        Commands::Validate { fix } => {
-            commands::validate::validate_aliases(*fix)?;
+        Commands::Validate { fix, only, strict } => {
+            let only = only
+                .iter()
+                .map(|s| s.parse())
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let issue_count = commands::validate::validate_aliases(*fix, &only)?;
+            if *strict && issue_count > 0 {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Commands::Used { alias, hook } => {
+            if let Some(shell) = hook {
+                commands::usage::print_hook(&shell.parse()?);
+            } else if let Some(alias) = alias {
+                commands::usage::mark_used(alias)?;
+            }
         }
-        Commands::Duplicates { remove } => {
-            commands::validate::check_duplicates(*remove)?;
+        Commands::Doctor { fix } => {
+            commands::doctor::run_doctor(*fix)?;
+        }
+        Commands::Repair { encoding } => {
+            if !*encoding {
+                anyhow::bail!("Nothing to repair. Use --encoding to remove lines that are not valid UTF-8");
+            }
+            commands::repair::repair_encoding()?;
+        }
+        Commands::Duplicates { remove, dry_run } => {
+            commands::validate::check_duplicates(*remove, *dry_run)?;
         }
         Commands::Interactive => {
             commands::interactive::run_interactive_mode()?;
@@ -364,21 +683,59 @@ fn main() -> anyhow::Result<()> {
             ConfigAction::Reset => {
                 commands::config::reset_config()?;
             }
+            ConfigAction::Validate => {
+                if !commands::config::validate_config()? {
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
         },
-        Commands::Stats => {
-            commands::stats::show_stats()?;
+        Commands::Stats {
+            watch,
+            interval,
+            format,
+            by_length,
+        } => {
+            if *watch && output == OutputFormat::Json {
+                anyhow::bail!("--json cannot be combined with --watch");
+            }
+            let format = legacy_format(format.as_deref(), output)?;
+            commands::stats::show_stats(*watch, *interval, format, *by_length)?;
         }
-        Commands::Export { format, output } => {
+        Commands::Export {
+            format,
+            output,
+            output_dir,
+            pretty: _,
+            compact,
+            sort,
+        } => {
             let format = format.parse()?;
-            commands::import_export::export_aliases(format, output.as_deref())?;
+            commands::import_export::export_aliases(
+                format,
+                output.as_deref(),
+                output_dir.as_deref(),
+                *compact,
+                *sort,
+            )?;
         }
         Commands::Import {
             source,
             format,
             dry_run,
+            on_conflict,
+            rename_prefix,
+            tags,
         } => {
             let source = source.parse()?;
-            commands::import_export::import_aliases(source, format.as_deref(), *dry_run)?;
+            let on_conflict = on_conflict.as_deref().map(str::parse).transpose()?;
+            commands::import_export::import_aliases(
+                source,
+                format.as_deref(),
+                *dry_run,
+                on_conflict,
+                rename_prefix.as_deref(),
+                tags,
+            )?;
         }
         Commands::Template { action } => match action {
             TemplateAction::Add {
@@ -395,15 +752,31 @@ fn main() -> anyhow::Result<()> {
                 )?;
             }
             TemplateAction::List { category } => {
-                commands::templates::list_templates(category.as_deref())?;
+                commands::templates::list_templates(category.as_deref(), output)?;
             }
             TemplateAction::Use {
                 name,
                 params,
                 alias_name,
+                stdout,
+                snippet,
+                output,
+                all_defaults,
             } => {
                 let param_map = parse_template_params(params.as_deref())?;
-                commands::templates::use_template(name, &param_map, alias_name.as_deref())?;
+                let target = match output {
+                    Some(path) => commands::templates::TemplateTarget::File(path.clone()),
+                    None if *stdout => commands::templates::TemplateTarget::Command,
+                    None if *snippet => commands::templates::TemplateTarget::Snippet,
+                    None => commands::templates::TemplateTarget::Aliases,
+                };
+                commands::templates::use_template(
+                    name,
+                    &param_map,
+                    alias_name.as_deref(),
+                    &target,
+                    *all_defaults,
+                )?;
             }
             TemplateAction::Remove { name } => {
                 commands::templates::remove_template(name)?;
@@ -441,8 +814,9 @@ fn main() -> anyhow::Result<()> {
                     icon.as_deref(),
                 )?;
             }
-            CategoryAction::List { tree, counts } => {
-                commands::categories::list_categories(*tree, *counts)?;
+            CategoryAction::List { tree, counts, sort } => {
+                let sort = sort.as_deref().map(str::parse).transpose()?;
+                commands::categories::list_categories(*tree, *counts, sort, output)?;
             }
             CategoryAction::Remove { name, force } => {
                 commands::categories::remove_category(name, *force)?;
@@ -450,48 +824,97 @@ fn main() -> anyhow::Result<()> {
             CategoryAction::Move { alias, category } => {
                 commands::categories::move_alias_to_category(alias, category)?;
             }
-            CategoryAction::Show { name } => {
-                commands::categories::show_category(name)?;
+            CategoryAction::Show { name, recursive } => {
+                commands::categories::show_category(name, *recursive, output)?;
             }
             CategoryAction::Group => {
                 commands::categories::group_aliases_by_category()?;
             }
         },
-        Commands::Completion { shell } => {
+        Commands::Tag { action } => match action {
+            TagAction::Rename { old, new } => {
+                commands::tags::rename_tag(old, new)?;
+            }
+            TagAction::Delete { name } => {
+                commands::tags::delete_tag(name)?;
+            }
+            TagAction::Merge {
+                first,
+                second,
+                into,
+            } => {
+                commands::tags::merge_tags(first, second, into)?;
+            }
+            TagAction::List { sort } => {
+                commands::tags::list_tags(sort.parse()?, output)?;
+            }
+        },
+        Commands::Tags { sort } => {
+            let sort = sort.parse()?;
+            commands::tags::list_tags(sort, output)?;
+        }
+        Commands::Completion {
+            shell,
+            output_dir,
+            aliases,
+        } => {
             let shell = shell.parse()?;
-            commands::shell_integration::generate_completion_script(shell)?;
+            if *aliases {
+                commands::shell_integration::generate_alias_completions(shell, output_dir.as_deref())?;
+            } else {
+                commands::shell_integration::generate_completion_script(shell, output_dir.as_deref())?;
+            }
         }
         Commands::Sync { action } => match action {
             SyncAction::Init { remote, branch } => {
                 commands::sync::init_sync(remote.as_deref(), branch.as_deref())?;
             }
-            SyncAction::Push => {
-                commands::sync::push_sync()?;
+            SyncAction::Clone { url, branch, force } => {
+                commands::sync::clone_sync(url, branch.as_deref(), *force)?;
+            }
+            SyncAction::Push { force, yes } => {
+                commands::sync::push_sync(*force, *yes)?;
             }
             SyncAction::Pull => {
                 commands::sync::pull_sync()?;
             }
             SyncAction::Status => {
-                commands::sync::sync_status()?;
+                commands::sync::sync_status(output)?;
+            }
+            SyncAction::Diff { remote } => {
+                commands::sync::diff_sync(*remote)?;
             }
             SyncAction::Remote { action } => match action {
                 RemoteAction::Add { url, name } => {
                     commands::sync::add_remote(url, name.as_deref())?;
                 }
                 RemoteAction::List => {
-                    println!("List remotes feature coming soon");
+                    commands::sync::list_remotes()?;
                 }
             },
             SyncAction::Reset => {
                 commands::sync::reset_sync()?;
             }
         },
-        Commands::Share { alias, method } => {
-            commands::sync::share_alias(alias, method)?;
+        Commands::Share {
+            alias,
+            method,
+            output,
+            output_dir,
+            format,
+        } => {
+            let format = format.as_deref().map(str::parse).transpose()?;
+            commands::sync::share_alias(
+                alias,
+                method,
+                output.as_deref(),
+                output_dir.as_deref(),
+                format,
+            )?;
         }
         Commands::Plugin { action } => match action {
             PluginAction::List { all } => {
-                commands::plugins::list_plugins(*all)?;
+                commands::plugins::list_plugins(*all, output)?;
             }
             PluginAction::Install { plugin } => {
                 commands::plugins::install_plugin(plugin)?;
@@ -499,8 +922,8 @@ fn main() -> anyhow::Result<()> {
             PluginAction::Remove { name } => {
                 commands::plugins::remove_plugin(name)?;
             }
-            PluginAction::Enable { name } => {
-                commands::plugins::enable_plugin(name)?;
+            PluginAction::Enable { name, skip_deps } => {
+                commands::plugins::enable_plugin(name, *skip_deps)?;
             }
             PluginAction::Disable { name } => {
                 commands::plugins::disable_plugin(name)?;
@@ -508,17 +931,24 @@ fn main() -> anyhow::Result<()> {
             PluginAction::Show { name } => {
                 commands::plugins::show_plugin(name)?;
             }
+            PluginAction::Doctor => {
+                commands::plugins::doctor_plugins()?;
+            }
             PluginAction::Run {
                 plugin,
                 command,
+                timeout,
                 args,
             } => {
-                commands::plugins::execute_plugin_command(plugin, command, args)?;
+                commands::plugins::execute_plugin_command(plugin, command, args, *timeout)?;
             }
         },
         Commands::Uninstall => {
             commands::uninstall::uninstall()?;
         }
+        Commands::ServeClipboard => {
+            commands::sync::serve_clipboard()?;
+        }
         Commands::Update { check, force } => {
             if *check {
                 commands::update::run_check_only()?;
@@ -528,9 +958,29 @@ fn main() -> anyhow::Result<()> {
                 commands::update::run_update(false, false)?;
             }
         }
+        Commands::History { limit } => {
+            commands::history::show_history(*limit, output)?;
+        }
+        Commands::Alias { action } => match action {
+            AliasAction::Exists { alias, print } => {
+                if !commands::lookup::alias_exists(alias, *print, output)? {
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
+        },
+        Commands::Get {
+            alias,
+            quiet,
+            format,
+        } => {
+            let format = legacy_format(format.as_deref(), output)?;
+            if !commands::lookup::get_alias(alias, *quiet, format)? {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
 fn parse_template_params(
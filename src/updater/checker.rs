@@ -23,7 +23,7 @@ pub fn check_for_updates_background(check_interval_hours: i64) -> Result<()> {
         return Ok(());
     }
 
-    match get_latest_release(2) {
+    match get_latest_release(2, 0) {
         Ok(release) => {
             let current = current_version();
             let latest = &release.tag_name;
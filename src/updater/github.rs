@@ -1,5 +1,9 @@
 use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Local};
+use reqwest::StatusCode;
+use reqwest::blocking::Response;
 use serde::{Deserialize, Serialize};
+use std::thread;
 use std::time::Duration;
 
 const GITHUB_API_URL: &str = "https://api.github.com/repos/anggasct/shorty/releases/latest";
@@ -18,43 +22,137 @@ pub struct Asset {
     pub browser_download_url: String,
 }
 
-pub fn get_latest_release(timeout_secs: u64) -> Result<Release> {
+#[derive(Debug, PartialEq)]
+enum RetryDecision {
+    Retry,
+    RateLimited,
+    Fail,
+}
+
+fn retry_decision(status: StatusCode, rate_limit_remaining: Option<&str>) -> RetryDecision {
+    if status.is_server_error() {
+        RetryDecision::Retry
+    } else if status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && rate_limit_remaining == Some("0"))
+    {
+        RetryDecision::RateLimited
+    } else {
+        RetryDecision::Fail
+    }
+}
+
+fn rate_limit_error(response: &Response) -> anyhow::Error {
+    let reset = response
+        .headers()
+        .get("x-ratelimit-reset")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i64>().ok())
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0));
+
+    match reset {
+        Some(reset) => anyhow!(
+            "GitHub API rate limit exceeded. Try again after {}.",
+            reset.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+        ),
+        None => anyhow!("GitHub API rate limit exceeded. Try again later."),
+    }
+}
+
+pub fn get_latest_release(timeout_secs: u64, retries: usize) -> Result<Release> {
     let client = reqwest::blocking::Client::builder()
         .timeout(Duration::from_secs(timeout_secs))
         .user_agent(USER_AGENT)
         .build()
         .context("Failed to create HTTP client")?;
 
-    let response = client
-        .get(GITHUB_API_URL)
-        .send()
-        .context("Failed to fetch latest release from GitHub")?;
-
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "GitHub API returned error: {}",
-            response.status()
-        ));
+    let mut attempt: usize = 0;
+    loop {
+        let retry_delay = (attempt < retries).then(|| Duration::from_millis(500 << attempt));
+        attempt += 1;
+
+        let response = match client.get(GITHUB_API_URL).send() {
+            Ok(response) => response,
+            Err(err) if err.is_timeout() || err.is_connect() => match retry_delay {
+                Some(delay) => {
+                    thread::sleep(delay);
+                    continue;
+                }
+                None => return Err(err).context("Failed to fetch latest release from GitHub"),
+            },
+            Err(err) => return Err(err).context("Failed to fetch latest release from GitHub"),
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            return response
+                .json()
+                .context("Failed to parse GitHub API response");
+        }
+
+        let remaining = response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .and_then(|value| value.to_str().ok());
+
+        match retry_decision(status, remaining) {
+            RetryDecision::Retry => {
+                if let Some(delay) = retry_delay {
+                    thread::sleep(delay);
+                    continue;
+                }
+            }
+            RetryDecision::RateLimited => return Err(rate_limit_error(&response)),
+            RetryDecision::Fail => {}
+        }
+
+        return Err(anyhow!("GitHub API returned error: {}", status));
     }
-
-    let release: Release = response
-        .json()
-        .context("Failed to parse GitHub API response")?;
-
-    Ok(release)
 }
 
 pub fn compare_versions(current: &str, latest: &str) -> VersionComparison {
-    let current_clean = current.trim_start_matches('v');
-    let latest_clean = latest.trim_start_matches('v');
-
-    match current_clean.cmp(latest_clean) {
+    match version_key(current).cmp(&version_key(latest)) {
         std::cmp::Ordering::Less => VersionComparison::UpdateAvailable,
         std::cmp::Ordering::Equal => VersionComparison::UpToDate,
         std::cmp::Ordering::Greater => VersionComparison::Ahead,
     }
 }
 
+// A dot-separated pre-release identifier. Numeric identifiers compare by
+// value and sort before alphanumeric ones, as in semver.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum PreRelease {
+    Numeric(u64),
+    Text(String),
+}
+
+// Numeric major/minor/patch, then whether it is a final release, so that
+// "1.0.0-beta" sorts before "1.0.0" and "0.10.0" after "0.9.0".
+fn version_key(version: &str) -> ([u64; 3], bool, Vec<PreRelease>) {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split('+').next().unwrap_or_default();
+    let (core, pre_release) = match version.split_once('-') {
+        Some((core, pre_release)) => (core, pre_release),
+        None => (version, ""),
+    };
+
+    let mut numbers = [0; 3];
+    for (slot, part) in numbers.iter_mut().zip(core.split('.')) {
+        let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+        *slot = digits.parse().unwrap_or(0);
+    }
+
+    let identifiers = pre_release
+        .split('.')
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.parse() {
+            Ok(number) if part.bytes().all(|b| b.is_ascii_digit()) => PreRelease::Numeric(number),
+            _ => PreRelease::Text(part.to_string()),
+        })
+        .collect();
+
+    (numbers, pre_release.is_empty(), identifiers)
+}
+
 #[derive(Debug, PartialEq)]
 pub enum VersionComparison {
     UpdateAvailable,
@@ -87,6 +185,64 @@ pub fn find_asset_url(release: &Release) -> Result<String> {
         .ok_or_else(|| anyhow!("No binary found for platform: {}", binary_name))
 }
 
+pub fn find_checksum_url(release: &Release) -> Result<String> {
+    let binary_name = get_platform_binary_name();
+    let per_binary = format!("{}.sha256", binary_name);
+
+    release
+        .assets
+        .iter()
+        .find(|asset| asset.name == "SHA256SUMS" || asset.name == per_binary)
+        .map(|asset| asset.browser_download_url.clone())
+        .ok_or_else(|| anyhow!("No checksum file (SHA256SUMS) published for this release"))
+}
+
 pub fn current_version() -> &'static str {
     env!("CARGO_PKG_VERSION")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_errors_retry_and_client_errors_fail() {
+        assert_eq!(retry_decision(StatusCode::INTERNAL_SERVER_ERROR, None), RetryDecision::Retry);
+        assert_eq!(retry_decision(StatusCode::BAD_GATEWAY, Some("42")), RetryDecision::Retry);
+        assert_eq!(retry_decision(StatusCode::NOT_FOUND, None), RetryDecision::Fail);
+        assert_eq!(retry_decision(StatusCode::NOT_FOUND, Some("0")), RetryDecision::Fail);
+    }
+
+    #[test]
+    fn forbidden_is_rate_limited_only_when_the_quota_is_spent() {
+        assert_eq!(retry_decision(StatusCode::FORBIDDEN, Some("0")), RetryDecision::RateLimited);
+        assert_eq!(retry_decision(StatusCode::FORBIDDEN, Some("17")), RetryDecision::Fail);
+        assert_eq!(retry_decision(StatusCode::FORBIDDEN, None), RetryDecision::Fail);
+        assert_eq!(retry_decision(StatusCode::TOO_MANY_REQUESTS, None), RetryDecision::RateLimited);
+    }
+
+    #[test]
+    fn double_digit_components_compare_numerically() {
+        assert_eq!(compare_versions("0.9.0", "0.10.0"), VersionComparison::UpdateAvailable);
+        assert_eq!(compare_versions("0.2.0", "0.12.0"), VersionComparison::UpdateAvailable);
+        assert_eq!(compare_versions("v0.12.0", "0.2.0"), VersionComparison::Ahead);
+        assert_eq!(compare_versions("1.10.2", "v1.10.2"), VersionComparison::UpToDate);
+    }
+
+    #[test]
+    fn pre_releases_sort_before_the_release_and_by_number() {
+        for (older, newer) in [
+            ("1.0.0-beta", "1.0.0"),
+            ("1.0.0-beta.9", "1.0.0-beta.10"),
+            ("1.0.0-beta.2", "1.0.0-beta.11"),
+            ("1.0.0-alpha.5", "1.0.0-beta.1"),
+            ("1.0.0-beta", "1.0.0-beta.1"),
+        ] {
+            assert_eq!(
+                compare_versions(older, newer),
+                VersionComparison::UpdateAvailable,
+                "{older} < {newer}"
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result, anyhow};
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -31,6 +32,62 @@ pub fn download_binary(url: &str, dest: &Path) -> Result<()> {
     Ok(())
 }
 
+pub fn download_checksum(url: &str, binary_name: &str) -> Result<String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let response = client
+        .get(url)
+        .send()
+        .context("Failed to download checksum file")?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Checksum download failed with status: {}",
+            response.status()
+        ));
+    }
+
+    let content = response.text().context("Failed to read checksum file")?;
+    let lines: Vec<&str> = content.lines().filter(|line| !line.trim().is_empty()).collect();
+
+    // Accept both "<hash>  <file>" lines (SHA256SUMS) and a bare "<hash>".
+    let hash = lines
+        .iter()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            match parts.next() {
+                Some(name) if name.trim_start_matches('*') == binary_name => Some(hash),
+                None if lines.len() == 1 => Some(hash),
+                _ => None,
+            }
+        })
+        .next()
+        .ok_or_else(|| anyhow!("Checksum file has no entry for {}", binary_name))?;
+
+    Ok(hash.to_lowercase())
+}
+
+pub fn verify_checksum(path: &Path, expected: &str) -> Result<()> {
+    let content = fs::read(path)
+        .with_context(|| format!("Failed to read downloaded binary: {:?}", path))?;
+    let actual = format!("{:x}", Sha256::digest(&content));
+
+    if actual != expected {
+        fs::remove_file(path).ok();
+        return Err(anyhow!(
+            "Checksum mismatch for downloaded binary (expected {}, got {}). The download may be corrupted or tampered with; nothing was installed.",
+            expected,
+            actual
+        ));
+    }
+
+    Ok(())
+}
+
 pub fn get_current_binary_path() -> Result<PathBuf> {
     std::env::current_exe()
         .context("Failed to get current executable path")
@@ -1,10 +1,152 @@
-use std::fs;
-use std::path::PathBuf;
-use serde::{Deserialize, Serialize};
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+static LOCK_HELD: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => anyhow::bail!("Unsupported format: {}. Supported: text, json", s),
+        }
+    }
+}
+
+pub struct FileLock {
+    file: Option<fs::File>,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock; the file itself stays in place
+        // so every process keeps locking the same inode.
+        if self.file.take().is_some() {
+            LOCK_HELD.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+pub fn acquire_lock() -> Result<FileLock> {
+    if LOCK_HELD.swap(true, Ordering::SeqCst) {
+        return Ok(FileLock { file: None });
+    }
+
+    match lock_aliases_file() {
+        Ok(file) => Ok(FileLock { file: Some(file) }),
+        Err(e) => {
+            LOCK_HELD.store(false, Ordering::SeqCst);
+            Err(e)
+        }
+    }
+}
+
+// The lock is an OS advisory lock on `<aliases>.lock`, so it is released as
+// soon as its holder exits, however long the holder runs or however it dies.
+fn lock_aliases_file() -> Result<fs::File> {
+    let aliases_path = get_aliases_path()?;
+    let file_name = aliases_path
+        .file_name()
+        .unwrap_or_else(|| "aliases".as_ref());
+    let mut lock_name = file_name.to_os_string();
+    lock_name.push(".lock");
+    let lock_path = aliases_path.with_file_name(lock_name);
+    if let Some(parent) = lock_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open lock file {}", lock_path.display()))?;
+    let start = Instant::now();
+    let mut warned = false;
+
+    loop {
+        match file.try_lock() {
+            Ok(()) => {
+                file.set_len(0)?;
+                writeln!(file, "{}", std::process::id())?;
+                return Ok(file);
+            }
+            Err(fs::TryLockError::WouldBlock) => {
+                if start.elapsed() >= LOCK_TIMEOUT {
+                    let holder = fs::read_to_string(&lock_path).unwrap_or_default();
+                    anyhow::bail!(
+                        "Timed out waiting for another shorty process (pid {}) to finish",
+                        holder.trim()
+                    );
+                }
+                if !warned {
+                    eprintln!(
+                        "Another shorty process is modifying your aliases. Waiting up to {} seconds...",
+                        LOCK_TIMEOUT.as_secs()
+                    );
+                    warned = true;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(fs::TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+}
+
+pub fn get_aliases_path() -> Result<PathBuf> {
+    resolve_aliases_path(dirs::home_dir())
+}
+
+// The explicit locations are checked before the home directory is needed, so
+// SHORTY_ALIASES_PATH or an absolute `aliases.file_path` work without one.
+fn resolve_aliases_path(home_dir: Option<PathBuf>) -> Result<PathBuf> {
+    if let Some(custom_path) = std::env::var_os("SHORTY_ALIASES_PATH").filter(|p| !p.is_empty()) {
+        return Ok(PathBuf::from(custom_path));
+    }
+
+    let configured = crate::commands::config::load_config().aliases.file_path;
+    let configured = configured.trim();
+    let home_relative = if configured == "~" {
+        Some("")
+    } else {
+        configured.strip_prefix("~/")
+    };
+    let configured_path = match home_relative {
+        _ if configured.is_empty() => None,
+        Some(rest) => home_dir.as_ref().map(|home_dir| home_dir.join(rest)),
+        None => Some(PathBuf::from(configured)),
+    };
+
+    let Some(home_dir) = home_dir else {
+        return configured_path.map(create_parent).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Could not find home directory. Set SHORTY_ALIASES_PATH to the aliases file to use"
+            )
+        });
+    };
+    if let Some(path) =
+        configured_path.filter(|path| *path != home_dir.join(".shorty").join("aliases"))
+    {
+        return Ok(create_parent(path));
+    }
 
-pub fn get_aliases_path() -> PathBuf {
-    let home_dir = dirs::home_dir().expect("Could not find home directory");
     let shorty_dir = home_dir.join(".shorty");
     let new_path = shorty_dir.join("aliases");
     let old_path = home_dir.join(".shorty_aliases");
@@ -15,7 +157,7 @@ pub fn get_aliases_path() -> PathBuf {
     if old_path.exists() && !new_path.exists() {
         if let Err(e) = fs::copy(&old_path, &new_path) {
             eprintln!("Warning: Could not migrate aliases file: {e}");
-            return old_path;
+            return Ok(old_path);
         }
         let backup_path = home_dir.join(".shorty_aliases.backup");
         if let Err(e) = fs::rename(&old_path, &backup_path) {
@@ -26,7 +168,141 @@ pub fn get_aliases_path() -> PathBuf {
         }
     }
 
-    new_path
+    Ok(new_path)
+}
+
+fn create_parent(path: PathBuf) -> PathBuf {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if let Err(e) = fs::create_dir_all(parent) {
+            eprintln!("Warning: Could not create {}: {e}", parent.display());
+        }
+    }
+    path
+}
+
+pub fn write_aliases_atomic(content: &str) -> Result<()> {
+    write_atomic(&get_aliases_path()?, content)
+}
+
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = target
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid file path: {}", target.display()))?
+        .to_string_lossy();
+    let temp_path = dir.join(format!(".{file_name}.tmp.{}", std::process::id()));
+
+    let result = write_temp_and_rename(&temp_path, &target, content);
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result.with_context(|| format!("Failed to write {}", target.display()))
+}
+
+fn write_temp_and_rename(temp_path: &Path, target: &Path, content: &str) -> Result<()> {
+    let mut file = fs::File::create(temp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+
+    if let Ok(metadata) = fs::metadata(target) {
+        fs::set_permissions(temp_path, metadata.permissions())?;
+    }
+
+    match fs::rename(temp_path, target) {
+        Ok(()) => Ok(()),
+        #[cfg(windows)]
+        Err(_) if target.exists() => {
+            fs::remove_file(target)?;
+            fs::rename(temp_path, target)?;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn expand_home(path: &str) -> PathBuf {
+    if path == "~" {
+        if let Some(home_dir) = dirs::home_dir() {
+            return home_dir;
+        }
+    }
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home_dir) = dirs::home_dir() {
+            return home_dir.join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
+
+pub fn resolve_output_path(output_dir: Option<&str>, file_name: &str) -> Result<PathBuf> {
+    let config_dir = crate::commands::config::load_config()
+        .output
+        .default_output_dir;
+    let dir = output_dir
+        .map(str::to_string)
+        .or_else(|| Some(config_dir).filter(|d| !d.trim().is_empty()));
+
+    match dir {
+        Some(dir) => {
+            let dir = expand_home(&dir);
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Could not create output directory {}", dir.display()))?;
+            Ok(dir.join(file_name))
+        }
+        None => Ok(PathBuf::from(file_name)),
+    }
+}
+
+pub fn get_extra_aliases_paths() -> Vec<PathBuf> {
+    crate::commands::config::load_config()
+        .aliases
+        .extra_files
+        .iter()
+        .map(|path| expand_home(path))
+        .collect()
+}
+
+pub fn read_aliases_file(path: &Path) -> Result<String> {
+    let bytes =
+        fs::read(path).with_context(|| format!("Failed to read aliases from {:?}", path))?;
+    String::from_utf8(bytes).map_err(|e| {
+        let valid = &e.as_bytes()[..e.utf8_error().valid_up_to()];
+        let line = valid.iter().filter(|&&b| b == b'\n').count() + 1;
+        anyhow::anyhow!(
+            "Aliases file {} is not valid UTF-8 (line ~{}). Run 'shorty repair --encoding' to salvage the valid lines",
+            path.display(),
+            line
+        )
+    })
+}
+
+pub fn read_aliases_sources() -> Result<Vec<(PathBuf, String)>> {
+    let mut sources = Vec::new();
+    let primary_path = get_aliases_path()?;
+
+    if primary_path.exists() {
+        let content = read_aliases_file(&primary_path)?;
+        sources.push((primary_path.clone(), content));
+    }
+
+    for path in get_extra_aliases_paths() {
+        if path == primary_path {
+            continue;
+        }
+        if !path.exists() {
+            eprintln!("Warning: Extra aliases file not found: {}", path.display());
+            continue;
+        }
+        let content = read_aliases_file(&path)?;
+        sources.push((path, content));
+    }
+
+    Ok(sources)
 }
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
@@ -42,19 +318,20 @@ pub struct UpdateState {
     pub skipped_versions: Vec<String>,
 }
 
-pub fn get_state_path() -> PathBuf {
-    let home_dir = dirs::home_dir().expect("Could not find home directory");
+pub fn get_state_path() -> Result<PathBuf> {
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
     let shorty_dir = home_dir.join(".shorty");
 
     if let Err(e) = fs::create_dir_all(&shorty_dir) {
         eprintln!("Warning: Could not create .shorty directory: {e}");
     }
 
-    shorty_dir.join("shorty.json")
+    Ok(shorty_dir.join("shorty.json"))
 }
 
 pub fn read_state() -> Result<ShortyState> {
-    let state_path = get_state_path();
+    let state_path = get_state_path()?;
 
     if !state_path.exists() {
         return Ok(ShortyState::default());
@@ -63,17 +340,17 @@ pub fn read_state() -> Result<ShortyState> {
     let content = fs::read_to_string(&state_path)
         .with_context(|| format!("Failed to read state from {:?}", state_path))?;
 
-    let state: ShortyState = serde_json::from_str(&content)
-        .with_context(|| "Failed to parse state JSON")?;
+    let state: ShortyState =
+        serde_json::from_str(&content).with_context(|| "Failed to parse state JSON")?;
 
     Ok(state)
 }
 
 pub fn write_state(state: &ShortyState) -> Result<()> {
-    let state_path = get_state_path();
+    let state_path = get_state_path()?;
 
-    let content = serde_json::to_string_pretty(state)
-        .with_context(|| "Failed to serialize state")?;
+    let content =
+        serde_json::to_string_pretty(state).with_context(|| "Failed to serialize state")?;
 
     fs::write(&state_path, content)
         .with_context(|| format!("Failed to write state to {:?}", state_path))?;
@@ -90,3 +367,245 @@ where
     write_state(&state)?;
     Ok(())
 }
+
+/// Usage counts stored in `~/.shorty/usage.json`:
+///
+/// ```json
+/// { "version": 1, "aliases": { "gs": { "count": 12, "last_used": "2024-01-01 12:00:00" } } }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UsageData {
+    #[serde(default = "usage_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub aliases: BTreeMap<String, AliasUsage>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct AliasUsage {
+    pub count: u64,
+    pub last_used: Option<String>,
+}
+
+fn usage_version() -> u32 {
+    1
+}
+
+impl Default for UsageData {
+    fn default() -> Self {
+        UsageData {
+            version: usage_version(),
+            aliases: BTreeMap::new(),
+        }
+    }
+}
+
+pub fn get_usage_path() -> Result<PathBuf> {
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    Ok(home_dir.join(".shorty").join("usage.json"))
+}
+
+pub fn read_usage() -> Result<UsageData> {
+    let usage_path = get_usage_path()?;
+
+    if !usage_path.exists() {
+        return Ok(UsageData::default());
+    }
+
+    let content = fs::read_to_string(&usage_path)
+        .with_context(|| format!("Failed to read usage from {:?}", usage_path))?;
+
+    match serde_json::from_str(&content) {
+        Ok(usage) => Ok(usage),
+        Err(e) => {
+            eprintln!(
+                "Warning: ignoring unreadable usage data in {:?} ({}); counts start from zero",
+                usage_path, e
+            );
+            Ok(UsageData::default())
+        }
+    }
+}
+
+pub fn record_usage(alias: &str) -> Result<()> {
+    let _lock = acquire_lock()?;
+    let usage_path = get_usage_path()?;
+    let mut usage = read_usage()?;
+
+    let entry = usage.aliases.entry(alias.to_string()).or_default();
+    entry.count += 1;
+    entry.last_used = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+
+    if let Some(parent) = usage_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content =
+        serde_json::to_string_pretty(&usage).with_context(|| "Failed to serialize usage")?;
+    write_atomic(&usage_path, &content)
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::ffi::OsString;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Mutex, MutexGuard};
+
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Points `HOME` at an empty temporary directory until dropped. Tests that
+    /// touch files under the home directory hold one so they run one at a time.
+    pub(crate) struct FakeHome {
+        path: PathBuf,
+        old_home: Option<OsString>,
+        _guard: MutexGuard<'static, ()>,
+    }
+
+    impl FakeHome {
+        pub(crate) fn new(name: &str) -> Self {
+            let guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let path = std::env::temp_dir().join(format!("shorty-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+
+            let old_home = std::env::var_os("HOME");
+            std::env::set_var("HOME", &path);
+            std::env::remove_var("SHORTY_ALIASES_PATH");
+
+            FakeHome {
+                path,
+                old_home,
+                _guard: guard,
+            }
+        }
+
+        pub(crate) fn path(&self) -> &Path {
+            &self.path
+        }
+
+        /// Writes `content` to `~/.shorty/<file>` and returns its path.
+        pub(crate) fn write(&self, file: &str, content: &str) -> PathBuf {
+            let path = self.path.join(".shorty").join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        }
+    }
+
+    impl Drop for FakeHome {
+        fn drop(&mut self) {
+            match &self.old_home {
+                Some(home) => std::env::set_var("HOME", home),
+                None => std::env::remove_var("HOME"),
+            }
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::FakeHome;
+    use super::*;
+
+    #[test]
+    fn output_files_go_to_the_requested_directory() {
+        let home = FakeHome::new("output-dir");
+        let dir = home.path().join("out").join("nested");
+
+        let path = resolve_output_path(Some(dir.to_str().unwrap()), "aliases.json").unwrap();
+        assert_eq!(path, dir.join("aliases.json"));
+        assert!(dir.is_dir());
+
+        assert_eq!(
+            resolve_output_path(None, "aliases.json").unwrap(),
+            PathBuf::from("aliases.json")
+        );
+
+        let configured = home.path().join("exports");
+        let mut config = crate::commands::config::Config::default();
+        config.output.default_output_dir = configured.display().to_string();
+        config.save().unwrap();
+        let path = resolve_output_path(None, "qr.png").unwrap();
+        assert_eq!(path, configured.join("qr.png"));
+        assert!(configured.is_dir());
+    }
+
+    #[test]
+    fn leftover_lock_file_does_not_block_and_held_lock_does() {
+        let home = FakeHome::new("lock-file");
+        let lock_path = home.write("aliases.lock", "999999\n");
+
+        let lock = acquire_lock().unwrap();
+        assert_eq!(
+            fs::read_to_string(&lock_path).unwrap(),
+            format!("{}\n", std::process::id())
+        );
+        let other = fs::File::open(&lock_path).unwrap();
+        assert!(matches!(
+            other.try_lock(),
+            Err(fs::TryLockError::WouldBlock)
+        ));
+
+        drop(lock);
+        assert!(other.try_lock().is_ok());
+    }
+
+    #[test]
+    fn extra_files_are_merged_after_the_primary_file() {
+        let home = FakeHome::new("extra-files");
+        let primary = home.write("aliases", "alias gs='git status'\n");
+        let work = home.write("work-aliases", "alias kc='kubectl'\nalias tf='terraform'\n");
+        let mut config = crate::commands::config::Config::default();
+        config.aliases.extra_files = vec!["~/.shorty/work-aliases".to_string()];
+        config.save().unwrap();
+
+        let sources = read_aliases_sources().unwrap();
+
+        let paths: Vec<&PathBuf> = sources.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, [&primary, &work]);
+        let names: Vec<String> = sources
+            .iter()
+            .flat_map(|(_, content)| crate::alias::parse_content(content))
+            .map(|alias| alias.name)
+            .collect();
+        assert_eq!(names, ["gs", "kc", "tf"]);
+    }
+
+    #[test]
+    fn missing_home_is_an_error_unless_a_path_is_given() {
+        let home = FakeHome::new("no-home");
+
+        let error = resolve_aliases_path(None).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Could not find home directory"),
+            "{error}"
+        );
+
+        let explicit = home.path().join("explicit-aliases");
+        std::env::set_var("SHORTY_ALIASES_PATH", &explicit);
+        let resolved = resolve_aliases_path(None);
+        std::env::remove_var("SHORTY_ALIASES_PATH");
+        assert_eq!(resolved.unwrap(), explicit);
+    }
+
+    #[test]
+    fn invalid_utf8_names_the_line_and_the_repair_command() {
+        let home = FakeHome::new("invalid-utf8");
+        let path = home.path().join("aliases");
+        fs::write(&path, b"alias gs='git status'\nalias bad='echo \xff'\n").unwrap();
+
+        let message = read_aliases_file(&path).unwrap_err().to_string();
+
+        assert_eq!(
+            message,
+            format!(
+                "Aliases file {} is not valid UTF-8 (line ~2). Run 'shorty repair --encoding' to salvage the valid lines",
+                path.display()
+            )
+        );
+    }
+}
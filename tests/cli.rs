@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn temp_home(name: &str) -> PathBuf {
+    let home = std::env::temp_dir().join(format!("shorty-cli-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(home.join(".shorty")).unwrap();
+    fs::write(
+        home.join(".shorty").join("aliases"),
+        "alias gs='git status' # show status #tags:git\nalias ll='ls -la'\n",
+    )
+    .unwrap();
+    home
+}
+
+fn shorty(home: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_shorty"))
+        .args(args)
+        .env("HOME", home)
+        .env_remove("SHORTY_ALIASES_PATH")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn timing_flag_reports_elapsed_time_on_stderr() {
+    let home = temp_home("timing");
+
+    let timed = shorty(&home, &["--timing", "get", "gs", "--quiet"]);
+    let untimed = shorty(&home, &["get", "gs", "--quiet"]);
+    let _ = fs::remove_dir_all(&home);
+
+    assert!(timed.status.success());
+    assert_eq!(String::from_utf8_lossy(&timed.stdout), "git status\n");
+    let stderr = String::from_utf8_lossy(&timed.stderr);
+    assert!(
+        stderr.starts_with("Completed in ") && stderr.trim_end().ends_with('s'),
+        "{stderr}"
+    );
+
+    assert!(untimed.stderr.is_empty());
+}
+
+#[test]
+fn list_columns_renders_only_the_requested_columns_in_order() {
+    let home = temp_home("columns");
+
+    let output = shorty(&home, &["list", "--columns", "tags,name"]);
+    let _ = fs::remove_dir_all(&home);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, ["TAGS  NAME", "----------", "git   gs", "      ll"]);
+}
+
+#[test]
+fn strict_validate_fails_on_issues_and_still_reports_timing() {
+    let home = temp_home("strict");
+    fs::write(
+        home.join(".shorty").join("aliases"),
+        "alias gs='git status'\nalias broken='no-such-command-xyz --flag'\n",
+    )
+    .unwrap();
+
+    let strict = shorty(&home, &["--timing", "validate", "--strict"]);
+    let lenient = shorty(&home, &["validate"]);
+    let _ = fs::remove_dir_all(&home);
+
+    assert_eq!(strict.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&strict.stderr).starts_with("Completed in "));
+    assert!(lenient.status.success());
+}
+
+#[test]
+fn alias_exists_succeeds_for_a_known_name() {
+    let home = temp_home("exists-known");
+
+    let output = shorty(&home, &["alias", "exists", "gs", "--print"]);
+    let _ = fs::remove_dir_all(&home);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "git status\n");
+}
+
+#[test]
+fn alias_exists_fails_for_a_missing_name() {
+    let home = temp_home("exists-missing");
+
+    let output = shorty(&home, &["alias", "exists", "nope"]);
+    let _ = fs::remove_dir_all(&home);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn template_use_stdout_prints_the_command_without_touching_the_aliases() {
+    let home = temp_home("template-stdout");
+    let aliases_path = home.join(".shorty").join("aliases");
+    let before = fs::read(&aliases_path).unwrap();
+
+    let output = shorty(
+        &home,
+        &[
+            "template",
+            "use",
+            "docker_run",
+            "--params",
+            "image=nginx",
+            "--all-defaults",
+            "--stdout",
+        ],
+    );
+    let after = fs::read(&aliases_path).unwrap();
+    let _ = fs::remove_dir_all(&home);
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "docker run -it --rm  nginx /bin/bash\n"
+    );
+    assert_eq!(after, before);
+}